@@ -0,0 +1,32 @@
+use random::sha::Sha256;
+use std::hint;
+use std::time::{Duration, Instant};
+
+const SIZE: usize = 64 << 20;
+
+fn throughput(name: &str, mut f: impl FnMut(&[u8]) -> [u8; 32], message: &[u8]) {
+    let start = Instant::now();
+    let mut runs = 0;
+    while start.elapsed() < Duration::from_secs(2) || runs < 3 {
+        hint::black_box(f(hint::black_box(message)));
+        runs += 1;
+    }
+    let elapsed = start.elapsed().as_secs_f64();
+    let mib = (runs * message.len()) as f64 / f64::from(1 << 20);
+    println!("{:>8}: {:8.1} MiB/s", name, mib / elapsed);
+}
+
+fn main() {
+    let message: Vec<u8> = (0..=255).cycle().take(SIZE).collect();
+
+    throughput(
+        "extend",
+        |m| Sha256::new().extend(m).finalize_reset(),
+        &message,
+    );
+    throughput(
+        "update",
+        |m| Sha256::new().update(m).unwrap().finalize_reset(),
+        &message,
+    );
+}
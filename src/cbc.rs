@@ -0,0 +1,247 @@
+use crate::rijndael::{xor_block, Aes128, Aes192, Aes256, BlockCipher, Key, BLOCK_SIZE};
+use std::error;
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum PaddingError {
+    Misaligned,
+    Invalid,
+}
+
+impl fmt::Display for PaddingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PaddingError::Misaligned => write!(f, "data is not a whole number of blocks"),
+            PaddingError::Invalid => write!(f, "invalid PKCS#7 padding"),
+        }
+    }
+}
+
+impl error::Error for PaddingError {}
+
+pub fn pad(data: &mut Vec<u8>) {
+    let n = BLOCK_SIZE - data.len() % BLOCK_SIZE;
+    data.resize(data.len() + n, n as u8);
+}
+
+pub fn unpad(data: &[u8]) -> Result<&[u8], PaddingError> {
+    if data.is_empty() || !data.len().is_multiple_of(BLOCK_SIZE) {
+        return Err(PaddingError::Misaligned);
+    }
+
+    let last_block = &data[data.len() - BLOCK_SIZE..];
+    let n = last_block[BLOCK_SIZE - 1];
+    let mut invalid = (n == 0 || n as usize > BLOCK_SIZE) as u8;
+    for (i, &b) in last_block.iter().rev().enumerate() {
+        let in_padding = ((i as u8) < n) as u8;
+        invalid |= in_padding & (b != n) as u8;
+    }
+
+    if invalid != 0 {
+        return Err(PaddingError::Invalid);
+    }
+    Ok(&data[..data.len() - n as usize])
+}
+
+pub struct Cbc<C: BlockCipher> {
+    key: Key<C>,
+}
+
+pub type Aes128Cbc = Cbc<Aes128>;
+pub type Aes192Cbc = Cbc<Aes192>;
+pub type Aes256Cbc = Cbc<Aes256>;
+
+impl<C: BlockCipher> Cbc<C> {
+    pub fn new(key: &C::Key) -> Self {
+        Cbc { key: Key::new(key) }
+    }
+
+    pub fn encrypt_no_padding(
+        &self,
+        iv: &[u8; BLOCK_SIZE],
+        data: &mut [u8],
+    ) -> Result<(), PaddingError> {
+        if !data.len().is_multiple_of(BLOCK_SIZE) {
+            return Err(PaddingError::Misaligned);
+        }
+
+        let mut block = *iv;
+        for chunk in data.chunks_exact_mut(BLOCK_SIZE) {
+            xor_block(&mut block, chunk);
+            self.key.encrypt_block(&mut block);
+            chunk.copy_from_slice(&block);
+        }
+        Ok(())
+    }
+
+    pub fn decrypt_no_padding(
+        &self,
+        iv: &[u8; BLOCK_SIZE],
+        data: &mut [u8],
+    ) -> Result<(), PaddingError> {
+        if !data.len().is_multiple_of(BLOCK_SIZE) {
+            return Err(PaddingError::Misaligned);
+        }
+
+        let mut previous = *iv;
+        let mut block = [0; BLOCK_SIZE];
+        for chunk in data.chunks_exact_mut(BLOCK_SIZE) {
+            block.copy_from_slice(chunk);
+            self.key.decrypt_block(&mut block);
+            xor_block(&mut block, &previous);
+            previous.copy_from_slice(chunk);
+            chunk.copy_from_slice(&block);
+        }
+        Ok(())
+    }
+
+    pub fn encrypt(&self, iv: &[u8; BLOCK_SIZE], plaintext: &[u8]) -> Vec<u8> {
+        let mut res = plaintext.to_vec();
+        pad(&mut res);
+        self.encrypt_no_padding(iv, &mut res)
+            .expect("padded data is block aligned");
+        res
+    }
+
+    pub fn decrypt(
+        &self,
+        iv: &[u8; BLOCK_SIZE],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, PaddingError> {
+        let mut res = ciphertext.to_vec();
+        self.decrypt_no_padding(iv, &mut res)?;
+        let len = unpad(&res)?.len();
+        res.truncate(len);
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_vectors::{AES128_KEY, AES192_KEY, AES256_KEY, PLAINTEXT};
+
+    const IV: [u8; 16] = [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, //
+        0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, //
+    ];
+
+    const AES256_CIPHERTEXT: [u8; 64] = [
+        0xf5, 0x8c, 0x4c, 0x04, 0xd6, 0xe5, 0xf1, 0xba, //
+        0x77, 0x9e, 0xab, 0xfb, 0x5f, 0x7b, 0xfb, 0xd6, //
+        0x9c, 0xfc, 0x4e, 0x96, 0x7e, 0xdb, 0x80, 0x8d, //
+        0x67, 0x9f, 0x77, 0x7b, 0xc6, 0x70, 0x2c, 0x7d, //
+        0x39, 0xf2, 0x33, 0x69, 0xa9, 0xd9, 0xba, 0xcf, //
+        0xa5, 0x30, 0xe2, 0x63, 0x04, 0x23, 0x14, 0x61, //
+        0xb2, 0xeb, 0x05, 0xe2, 0xc3, 0x9b, 0xe9, 0xfc, //
+        0xda, 0x6c, 0x19, 0x07, 0x8c, 0x6a, 0x9d, 0x1b, //
+    ];
+
+    fn check_vector<C: BlockCipher>(key: &C::Key, ciphertext: &[u8]) {
+        let cbc = Cbc::<C>::new(key);
+        let mut data = PLAINTEXT;
+        cbc.encrypt_no_padding(&IV, &mut data).unwrap();
+        assert_eq!(ciphertext, &data[..]);
+        cbc.decrypt_no_padding(&IV, &mut data).unwrap();
+        assert_eq!(PLAINTEXT[..], data[..]);
+    }
+
+    #[test]
+    fn test_cbc_aes128() {
+        check_vector::<Aes128>(
+            &AES128_KEY,
+            &[
+                0x76, 0x49, 0xab, 0xac, 0x81, 0x19, 0xb2, 0x46, //
+                0xce, 0xe9, 0x8e, 0x9b, 0x12, 0xe9, 0x19, 0x7d, //
+                0x50, 0x86, 0xcb, 0x9b, 0x50, 0x72, 0x19, 0xee, //
+                0x95, 0xdb, 0x11, 0x3a, 0x91, 0x76, 0x78, 0xb2, //
+                0x73, 0xbe, 0xd6, 0xb8, 0xe3, 0xc1, 0x74, 0x3b, //
+                0x71, 0x16, 0xe6, 0x9e, 0x22, 0x22, 0x95, 0x16, //
+                0x3f, 0xf1, 0xca, 0xa1, 0x68, 0x1f, 0xac, 0x09, //
+                0x12, 0x0e, 0xca, 0x30, 0x75, 0x86, 0xe1, 0xa7, //
+            ],
+        );
+    }
+
+    #[test]
+    fn test_cbc_aes192() {
+        check_vector::<Aes192>(
+            &AES192_KEY,
+            &[
+                0x4f, 0x02, 0x1d, 0xb2, 0x43, 0xbc, 0x63, 0x3d, //
+                0x71, 0x78, 0x18, 0x3a, 0x9f, 0xa0, 0x71, 0xe8, //
+                0xb4, 0xd9, 0xad, 0xa9, 0xad, 0x7d, 0xed, 0xf4, //
+                0xe5, 0xe7, 0x38, 0x76, 0x3f, 0x69, 0x14, 0x5a, //
+                0x57, 0x1b, 0x24, 0x20, 0x12, 0xfb, 0x7a, 0xe0, //
+                0x7f, 0xa9, 0xba, 0xac, 0x3d, 0xf1, 0x02, 0xe0, //
+                0x08, 0xb0, 0xe2, 0x79, 0x88, 0x59, 0x88, 0x81, //
+                0xd9, 0x20, 0xa9, 0xe6, 0x4f, 0x56, 0x15, 0xcd, //
+            ],
+        );
+    }
+
+    #[test]
+    fn test_cbc_aes256() {
+        check_vector::<Aes256>(&AES256_KEY, &AES256_CIPHERTEXT);
+    }
+
+    #[test]
+    fn test_padded() {
+        let cbc = Aes256Cbc::new(&AES256_KEY);
+        let ciphertext = cbc.encrypt(&IV, &PLAINTEXT);
+        assert_eq!(80, ciphertext.len());
+        assert_eq!(AES256_CIPHERTEXT[..], ciphertext[..64]);
+        assert_eq!(PLAINTEXT[..], cbc.decrypt(&IV, &ciphertext).unwrap()[..]);
+
+        for len in 0..=33 {
+            let ciphertext = cbc.encrypt(&IV, &PLAINTEXT[..len]);
+            assert_eq!((len / 16 + 1) * 16, ciphertext.len());
+            assert_eq!(PLAINTEXT[..len], cbc.decrypt(&IV, &ciphertext).unwrap()[..]);
+        }
+    }
+
+    #[test]
+    fn test_pad() {
+        let mut data = vec![0xae; 13];
+        pad(&mut data);
+        assert_eq!([&[0xae; 13][..], &[3, 3, 3]].concat(), data);
+        assert_eq!(&[0xae; 13][..], unpad(&data).unwrap());
+
+        let mut data = vec![0xae; 16];
+        pad(&mut data);
+        assert_eq!([&[0xae; 16][..], &[16; 16]].concat(), data);
+        assert_eq!(&[0xae; 16][..], unpad(&data).unwrap());
+    }
+
+    #[test]
+    fn test_unpad_errors() {
+        assert_eq!(Err(PaddingError::Misaligned), unpad(&[]));
+        assert_eq!(Err(PaddingError::Misaligned), unpad(&[1; 15]));
+        assert_eq!(Err(PaddingError::Invalid), unpad(&[0; 16]));
+        assert_eq!(Err(PaddingError::Invalid), unpad(&[17; 16]));
+
+        let mut data = [3; 16];
+        data[13] = 2;
+        assert_eq!(Err(PaddingError::Invalid), unpad(&data));
+        data[13] = 3;
+        data[12] = 2;
+        assert_eq!(&data[..13], unpad(&data).unwrap());
+    }
+
+    #[test]
+    fn test_decrypt_errors() {
+        let cbc = Aes256Cbc::new(&AES256_KEY);
+        assert_eq!(
+            Err(PaddingError::Misaligned),
+            cbc.decrypt(&IV, &AES256_CIPHERTEXT[..20])
+        );
+        assert_eq!(
+            Err(PaddingError::Misaligned),
+            cbc.encrypt_no_padding(&IV, &mut [0; 20])
+        );
+
+        let mut ciphertext = cbc.encrypt(&IV, &PLAINTEXT[..10]);
+        ciphertext[15] ^= 1;
+        assert_eq!(Err(PaddingError::Invalid), cbc.decrypt(&IV, &ciphertext));
+    }
+}
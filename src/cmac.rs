@@ -0,0 +1,231 @@
+use crate::ct;
+use crate::galois::{self, Gf2_128Cmac};
+use crate::rijndael::{xor_block, Aes128, Aes192, Aes256, BlockCipher, Key, BLOCK_SIZE};
+
+pub struct Cmac<C: BlockCipher> {
+    key: Key<C>,
+    k1: Gf2_128Cmac,
+    k2: Gf2_128Cmac,
+    state: [u8; BLOCK_SIZE],
+    buffer: [u8; BLOCK_SIZE],
+    buffer_len: usize,
+}
+
+pub type Aes128Cmac = Cmac<Aes128>;
+pub type Aes192Cmac = Cmac<Aes192>;
+pub type Aes256Cmac = Cmac<Aes256>;
+
+impl<C: BlockCipher> Cmac<C> {
+    pub fn new(key: &C::Key) -> Self {
+        let key = Key::new(key);
+        let mut l = [0; BLOCK_SIZE];
+        key.encrypt_block(&mut l);
+        let k1 = galois::dbl(Gf2_128Cmac::from(l));
+
+        Cmac {
+            key,
+            k1,
+            k2: galois::dbl(k1),
+            state: [0; BLOCK_SIZE],
+            buffer: [0; BLOCK_SIZE],
+            buffer_len: 0,
+        }
+    }
+
+    pub fn mac(key: &C::Key, message: &[u8]) -> [u8; BLOCK_SIZE] {
+        Self::new(key).update(message).finalize()
+    }
+
+    pub fn update(&mut self, mut message: &[u8]) -> &mut Self {
+        while !message.is_empty() {
+            // The last block gets special treatment, so only process a full
+            // buffer once we know more data follows it.
+            if self.buffer_len == BLOCK_SIZE {
+                xor_block(&mut self.state, &self.buffer);
+                self.key.encrypt_block(&mut self.state);
+                self.buffer_len = 0;
+            }
+
+            let n = (BLOCK_SIZE - self.buffer_len).min(message.len());
+            self.buffer[self.buffer_len..self.buffer_len + n].copy_from_slice(&message[..n]);
+            self.buffer_len += n;
+            message = &message[n..];
+        }
+        self
+    }
+
+    pub fn finalize(&mut self) -> [u8; BLOCK_SIZE] {
+        let subkey = if self.buffer_len == BLOCK_SIZE {
+            self.k1
+        } else {
+            self.buffer[self.buffer_len] = 0x80;
+            self.buffer[self.buffer_len + 1..]
+                .iter_mut()
+                .for_each(|b| *b = 0);
+            self.k2
+        };
+
+        xor_block(&mut self.state, &self.buffer);
+        xor_block(&mut self.state, &<[u8; BLOCK_SIZE]>::from(subkey));
+        self.key.encrypt_block(&mut self.state);
+
+        let tag = self.state;
+        self.state = [0; BLOCK_SIZE];
+        self.buffer_len = 0;
+        tag
+    }
+
+    pub fn verify(&mut self, tag: &[u8]) -> bool {
+        ct::eq(&self.finalize(), tag)
+    }
+}
+
+pub fn prf_128(key: &[u8], message: &[u8]) -> [u8; BLOCK_SIZE] {
+    let mut k = [0; BLOCK_SIZE];
+    if key.len() == BLOCK_SIZE {
+        k.copy_from_slice(key);
+    } else {
+        k = Aes128Cmac::mac(&[0; BLOCK_SIZE], key);
+    }
+    Aes128Cmac::mac(&k, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_vectors::{AES128_KEY, AES256_KEY, PLAINTEXT};
+
+    #[test]
+    fn test_subkeys() {
+        let cmac = Cmac::<Aes128>::new(&AES128_KEY);
+        assert_eq!(Gf2_128Cmac(0xfbeed618357133667c85e08f7236a8de), cmac.k1);
+        assert_eq!(Gf2_128Cmac(0xf7ddac306ae266ccf90bc11ee46d513b), cmac.k2);
+
+        let cmac = Cmac::<Aes256>::new(&AES256_KEY);
+        assert_eq!(Gf2_128Cmac(0xcad1ed03299eedac2e9a99808621502f), cmac.k1);
+        assert_eq!(Gf2_128Cmac(0x95a3da06533ddb585d3533010c42a0d9), cmac.k2);
+    }
+
+    #[test]
+    fn test_aes128_cmac() {
+        let mac = |len| Cmac::<Aes128>::mac(&AES128_KEY, &PLAINTEXT[..len]);
+        assert_eq!(
+            [
+                0xbb, 0x1d, 0x69, 0x29, 0xe9, 0x59, 0x37, 0x28, //
+                0x7f, 0xa3, 0x7d, 0x12, 0x9b, 0x75, 0x67, 0x46, //
+            ],
+            mac(0)
+        );
+        assert_eq!(
+            [
+                0x07, 0x0a, 0x16, 0xb4, 0x6b, 0x4d, 0x41, 0x44, //
+                0xf7, 0x9b, 0xdd, 0x9d, 0xd0, 0x4a, 0x28, 0x7c, //
+            ],
+            mac(16)
+        );
+        assert_eq!(
+            [
+                0xdf, 0xa6, 0x67, 0x47, 0xde, 0x9a, 0xe6, 0x30, //
+                0x30, 0xca, 0x32, 0x61, 0x14, 0x97, 0xc8, 0x27, //
+            ],
+            mac(40)
+        );
+        assert_eq!(
+            [
+                0x51, 0xf0, 0xbe, 0xbf, 0x7e, 0x3b, 0x9d, 0x92, //
+                0xfc, 0x49, 0x74, 0x17, 0x79, 0x36, 0x3c, 0xfe, //
+            ],
+            mac(64)
+        );
+    }
+
+    #[test]
+    fn test_aes256_cmac() {
+        let mac = |len| Cmac::<Aes256>::mac(&AES256_KEY, &PLAINTEXT[..len]);
+        assert_eq!(
+            [
+                0x02, 0x89, 0x62, 0xf6, 0x1b, 0x7b, 0xf8, 0x9e, //
+                0xfc, 0x6b, 0x55, 0x1f, 0x46, 0x67, 0xd9, 0x83, //
+            ],
+            mac(0)
+        );
+        assert_eq!(
+            [
+                0x28, 0xa7, 0x02, 0x3f, 0x45, 0x2e, 0x8f, 0x82, //
+                0xbd, 0x4b, 0xf2, 0x8d, 0x8c, 0x37, 0xc3, 0x5c, //
+            ],
+            mac(16)
+        );
+        assert_eq!(
+            [
+                0xaa, 0xf3, 0xd8, 0xf1, 0xde, 0x56, 0x40, 0xc2, //
+                0x32, 0xf5, 0xb1, 0x69, 0xb9, 0xc9, 0x11, 0xe6, //
+            ],
+            mac(40)
+        );
+        assert_eq!(
+            [
+                0xe1, 0x99, 0x21, 0x90, 0x54, 0x9f, 0x6e, 0xd5, //
+                0x69, 0x6a, 0x2c, 0x05, 0x6c, 0x31, 0x54, 0x10, //
+            ],
+            mac(64)
+        );
+    }
+
+    #[test]
+    fn test_incremental() {
+        let mut cmac = Cmac::<Aes256>::new(&AES256_KEY);
+        for len in [0, 15, 16, 17, 40, 64] {
+            let expected = Cmac::<Aes256>::mac(&AES256_KEY, &PLAINTEXT[..len]);
+            for chunk in PLAINTEXT[..len].chunks(7) {
+                cmac.update(chunk);
+            }
+            assert_eq!(expected, cmac.finalize());
+            assert_eq!(expected, cmac.update(&PLAINTEXT[..len]).finalize());
+        }
+    }
+
+    #[test]
+    fn test_verify() {
+        let tag = Cmac::<Aes256>::mac(&AES256_KEY, &PLAINTEXT);
+        let mut cmac = Aes256Cmac::new(&AES256_KEY);
+        assert!(cmac.update(&PLAINTEXT).verify(&tag));
+        assert!(!cmac.update(&PLAINTEXT).verify(&tag[..8]));
+        assert!(!cmac.update(&PLAINTEXT[1..]).verify(&tag));
+    }
+
+    #[test]
+    fn test_prf_128() {
+        let key = [
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, //
+            0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, //
+            0xed, 0xcb, //
+        ];
+        let message = [
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, //
+            0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, //
+            0x10, 0x11, 0x12, 0x13, //
+        ];
+        assert_eq!(
+            [
+                0x84, 0xa3, 0x48, 0xa4, 0xa4, 0x5d, 0x23, 0x5b, //
+                0xab, 0xff, 0xfc, 0x0d, 0x2b, 0x4d, 0xa0, 0x9a, //
+            ],
+            prf_128(&key, &message)
+        );
+        assert_eq!(
+            [
+                0x98, 0x0a, 0xe8, 0x7b, 0x5f, 0x4c, 0x9c, 0x52, //
+                0x14, 0xf5, 0xb6, 0xa8, 0x45, 0x5e, 0x4c, 0x2d, //
+            ],
+            prf_128(&key[..16], &message)
+        );
+        assert_eq!(
+            [
+                0x29, 0x0d, 0x9e, 0x11, 0x2e, 0xdb, 0x09, 0xee, //
+                0x14, 0x1f, 0xcf, 0x64, 0xc0, 0xb7, 0x2f, 0x3d, //
+            ],
+            prf_128(&key[..10], &message)
+        );
+    }
+}
@@ -0,0 +1,28 @@
+use volatile::Volatile;
+
+pub fn eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut diff = Volatile::new(0_u8);
+    for (&x, &y) in a.iter().zip(b) {
+        diff.write(diff.read() | (x ^ y));
+    }
+
+    diff.read() == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eq() {
+        assert!(eq(&[], &[]));
+        assert!(eq(&[1, 2, 3], &[1, 2, 3]));
+        assert!(!eq(&[1, 2, 3], &[1, 2, 4]));
+        assert!(!eq(&[0x80, 2, 3], &[0, 2, 3]));
+        assert!(!eq(&[1, 2, 3], &[1, 2]));
+    }
+}
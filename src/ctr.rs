@@ -0,0 +1,195 @@
+use crate::rijndael::{Aes128, Aes192, Aes256, BlockCipher, Key, BLOCK_SIZE};
+use std::error;
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub struct CounterOverflow;
+
+impl fmt::Display for CounterOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CTR counter would wrap around")
+    }
+}
+
+impl error::Error for CounterOverflow {}
+
+pub struct Ctr<C: BlockCipher> {
+    key: Key<C>,
+    iv: u128,
+    counter: Option<u128>,
+    offset: usize,
+    keystream: [u8; BLOCK_SIZE],
+}
+
+pub type Aes128Ctr = Ctr<Aes128>;
+pub type Aes192Ctr = Ctr<Aes192>;
+pub type Aes256Ctr = Ctr<Aes256>;
+
+impl<C: BlockCipher> Ctr<C> {
+    pub fn new(key: &C::Key, iv: &[u8; BLOCK_SIZE]) -> Self {
+        let iv = u128::from_be_bytes(*iv);
+        Ctr {
+            key: Key::new(key),
+            iv,
+            counter: Some(iv),
+            offset: 0,
+            keystream: [0; BLOCK_SIZE],
+        }
+    }
+
+    fn next_keystream(&mut self, counter: u128) {
+        self.keystream = counter.to_be_bytes();
+        self.key.encrypt_block(&mut self.keystream);
+    }
+
+    pub fn apply_keystream(&mut self, data: &mut [u8]) -> Result<(), CounterOverflow> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        let last_block = ((self.offset + data.len() - 1) / BLOCK_SIZE) as u128;
+        let mut counter = self.counter.ok_or(CounterOverflow)?;
+        counter.checked_add(last_block).ok_or(CounterOverflow)?;
+
+        for byte in data.iter_mut() {
+            if self.offset == 0 {
+                self.next_keystream(counter);
+            }
+            *byte ^= self.keystream[self.offset];
+            self.offset += 1;
+            if self.offset == BLOCK_SIZE {
+                self.offset = 0;
+                self.counter = counter.checked_add(1);
+                counter = self.counter.unwrap_or(0);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn seek(&mut self, byte_offset: u64) -> Result<(), CounterOverflow> {
+        let counter = self
+            .iv
+            .checked_add((byte_offset / BLOCK_SIZE as u64) as u128)
+            .ok_or(CounterOverflow)?;
+        self.counter = Some(counter);
+        self.offset = (byte_offset % BLOCK_SIZE as u64) as usize;
+        if self.offset != 0 {
+            self.next_keystream(counter);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_vectors::{AES128_KEY, AES192_KEY, AES256_KEY, PLAINTEXT};
+
+    const COUNTER: [u8; 16] = [
+        0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7, //
+        0xf8, 0xf9, 0xfa, 0xfb, 0xfc, 0xfd, 0xfe, 0xff, //
+    ];
+
+    const AES256_CIPHERTEXT: [u8; 64] = [
+        0x60, 0x1e, 0xc3, 0x13, 0x77, 0x57, 0x89, 0xa5, //
+        0xb7, 0xa7, 0xf5, 0x04, 0xbb, 0xf3, 0xd2, 0x28, //
+        0xf4, 0x43, 0xe3, 0xca, 0x4d, 0x62, 0xb5, 0x9a, //
+        0xca, 0x84, 0xe9, 0x90, 0xca, 0xca, 0xf5, 0xc5, //
+        0x2b, 0x09, 0x30, 0xda, 0xa2, 0x3d, 0xe9, 0x4c, //
+        0xe8, 0x70, 0x17, 0xba, 0x2d, 0x84, 0x98, 0x8d, //
+        0xdf, 0xc9, 0xc5, 0x8d, 0xb6, 0x7a, 0xad, 0xa6, //
+        0x13, 0xc2, 0xdd, 0x08, 0x45, 0x79, 0x41, 0xa6, //
+    ];
+
+    #[test]
+    fn test_ctr_aes128() {
+        let mut data = PLAINTEXT;
+        Aes128Ctr::new(&AES128_KEY, &COUNTER)
+            .apply_keystream(&mut data)
+            .unwrap();
+        assert_eq!(
+            data[..],
+            [
+                0x87, 0x4d, 0x61, 0x91, 0xb6, 0x20, 0xe3, 0x26, //
+                0x1b, 0xef, 0x68, 0x64, 0x99, 0x0d, 0xb6, 0xce, //
+                0x98, 0x06, 0xf6, 0x6b, 0x79, 0x70, 0xfd, 0xff, //
+                0x86, 0x17, 0x18, 0x7b, 0xb9, 0xff, 0xfd, 0xff, //
+                0x5a, 0xe4, 0xdf, 0x3e, 0xdb, 0xd5, 0xd3, 0x5e, //
+                0x5b, 0x4f, 0x09, 0x02, 0x0d, 0xb0, 0x3e, 0xab, //
+                0x1e, 0x03, 0x1d, 0xda, 0x2f, 0xbe, 0x03, 0xd1, //
+                0x79, 0x21, 0x70, 0xa0, 0xf3, 0x00, 0x9c, 0xee, //
+            ][..]
+        );
+    }
+
+    #[test]
+    fn test_ctr_aes192() {
+        let mut data = PLAINTEXT;
+        Aes192Ctr::new(&AES192_KEY, &COUNTER)
+            .apply_keystream(&mut data)
+            .unwrap();
+        assert_eq!(
+            data[..],
+            [
+                0x1a, 0xbc, 0x93, 0x24, 0x17, 0x52, 0x1c, 0xa2, //
+                0x4f, 0x2b, 0x04, 0x59, 0xfe, 0x7e, 0x6e, 0x0b, //
+                0x09, 0x03, 0x39, 0xec, 0x0a, 0xa6, 0xfa, 0xef, //
+                0xd5, 0xcc, 0xc2, 0xc6, 0xf4, 0xce, 0x8e, 0x94, //
+                0x1e, 0x36, 0xb2, 0x6b, 0xd1, 0xeb, 0xc6, 0x70, //
+                0xd1, 0xbd, 0x1d, 0x66, 0x56, 0x20, 0xab, 0xf7, //
+                0x4f, 0x78, 0xa7, 0xf6, 0xd2, 0x98, 0x09, 0x58, //
+                0x5a, 0x97, 0xda, 0xec, 0x58, 0xc6, 0xb0, 0x50, //
+            ][..]
+        );
+    }
+
+    #[test]
+    fn test_ctr_aes256() {
+        let mut data = PLAINTEXT;
+        let mut ctr = Aes256Ctr::new(&AES256_KEY, &COUNTER);
+        ctr.apply_keystream(&mut data).unwrap();
+        assert_eq!(AES256_CIPHERTEXT[..], data[..]);
+
+        ctr.seek(0).unwrap();
+        ctr.apply_keystream(&mut data).unwrap();
+        assert_eq!(PLAINTEXT[..], data[..]);
+    }
+
+    #[test]
+    fn test_split_requests() {
+        let mut data = PLAINTEXT;
+        let mut ctr = Aes256Ctr::new(&AES256_KEY, &COUNTER);
+        for chunk in data.chunks_mut(7) {
+            ctr.apply_keystream(chunk).unwrap();
+        }
+        assert_eq!(AES256_CIPHERTEXT[..], data[..]);
+    }
+
+    #[test]
+    fn test_seek() {
+        let mut ctr = Aes256Ctr::new(&AES256_KEY, &COUNTER);
+        for offset in [0, 5, 16, 17, 40, 63] {
+            let mut data = PLAINTEXT;
+            ctr.seek(offset as u64).unwrap();
+            ctr.apply_keystream(&mut data[offset..]).unwrap();
+            assert_eq!(AES256_CIPHERTEXT[offset..], data[offset..]);
+        }
+    }
+
+    #[test]
+    fn test_counter_overflow() {
+        let iv = (u128::MAX - 1).to_be_bytes();
+        let mut ctr = Aes256Ctr::new(&AES256_KEY, &iv);
+        let mut data = [0; 33];
+        assert_eq!(Err(CounterOverflow), ctr.apply_keystream(&mut data));
+        assert_eq!([0; 33][..], data[..]);
+
+        ctr.apply_keystream(&mut data[..20]).unwrap();
+        ctr.apply_keystream(&mut data[20..32]).unwrap();
+        assert_eq!(Err(CounterOverflow), ctr.apply_keystream(&mut data[32..]));
+        ctr.apply_keystream(&mut []).unwrap();
+
+        assert_eq!(Err(CounterOverflow), ctr.seek(32));
+        ctr.seek(31).unwrap();
+        ctr.apply_keystream(&mut data[31..32]).unwrap();
+    }
+}
@@ -0,0 +1,225 @@
+use crate::fortuna::{self, Accumulator, Clock, POOL_COUNT};
+use std::error;
+use std::fmt;
+use std::fs::File;
+use std::hint;
+use std::io;
+use std::io::Read;
+use std::time::Instant;
+
+const EVENT_SIZE: usize = 32;
+const JITTER_SAMPLES: usize = 8;
+
+#[derive(Debug)]
+pub enum EntropyError {
+    GetRandom(getrandom::Error),
+    Io(io::Error),
+    NoJitter,
+    Accumulator(fortuna::Error),
+}
+
+impl fmt::Display for EntropyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EntropyError::GetRandom(e) => write!(f, "getrandom failed: {}", e),
+            EntropyError::Io(e) => write!(f, "cannot read entropy device: {}", e),
+            EntropyError::NoJitter => write!(f, "timer shows no measurable jitter"),
+            EntropyError::Accumulator(e) => write!(f, "cannot add event: {}", e),
+        }
+    }
+}
+
+impl error::Error for EntropyError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            EntropyError::GetRandom(e) => Some(e),
+            EntropyError::Io(e) => Some(e),
+            EntropyError::NoJitter => None,
+            EntropyError::Accumulator(e) => Some(e),
+        }
+    }
+}
+
+impl From<getrandom::Error> for EntropyError {
+    fn from(e: getrandom::Error) -> Self {
+        EntropyError::GetRandom(e)
+    }
+}
+
+impl From<io::Error> for EntropyError {
+    fn from(e: io::Error) -> Self {
+        EntropyError::Io(e)
+    }
+}
+
+impl From<fortuna::Error> for EntropyError {
+    fn from(e: fortuna::Error) -> Self {
+        EntropyError::Accumulator(e)
+    }
+}
+
+pub trait EntropySource {
+    fn source_id(&self) -> u8;
+
+    fn gather(&mut self, buf: &mut [u8]) -> Result<(), EntropyError>;
+}
+
+pub struct GetRandom {
+    source_id: u8,
+}
+
+impl GetRandom {
+    pub fn new(source_id: u8) -> GetRandom {
+        GetRandom { source_id }
+    }
+}
+
+impl EntropySource for GetRandom {
+    fn source_id(&self) -> u8 {
+        self.source_id
+    }
+
+    fn gather(&mut self, buf: &mut [u8]) -> Result<(), EntropyError> {
+        getrandom::getrandom(buf)?;
+        Ok(())
+    }
+}
+
+pub struct DevUrandom {
+    source_id: u8,
+    file: Option<File>,
+}
+
+impl DevUrandom {
+    pub fn new(source_id: u8) -> DevUrandom {
+        DevUrandom {
+            source_id,
+            file: None,
+        }
+    }
+}
+
+impl EntropySource for DevUrandom {
+    fn source_id(&self) -> u8 {
+        self.source_id
+    }
+
+    fn gather(&mut self, buf: &mut [u8]) -> Result<(), EntropyError> {
+        if self.file.is_none() {
+            self.file = Some(File::open("/dev/urandom")?);
+        }
+        if let Some(file) = &mut self.file {
+            file.read_exact(buf)?;
+        }
+        Ok(())
+    }
+}
+
+pub struct TimingJitter {
+    source_id: u8,
+}
+
+impl TimingJitter {
+    pub fn new(source_id: u8) -> TimingJitter {
+        TimingJitter { source_id }
+    }
+
+    fn sample() -> u128 {
+        let start = Instant::now();
+        let mut acc = 0_u64;
+        for i in 0..64 {
+            acc = hint::black_box(acc.rotate_left(7) ^ i);
+        }
+        start.elapsed().as_nanos()
+    }
+}
+
+impl EntropySource for TimingJitter {
+    fn source_id(&self) -> u8 {
+        self.source_id
+    }
+
+    fn gather(&mut self, buf: &mut [u8]) -> Result<(), EntropyError> {
+        let first = Self::sample();
+        let mut varied = false;
+
+        for byte in buf.iter_mut() {
+            let mut acc = 0_u8;
+            for _ in 0..JITTER_SAMPLES {
+                let sample = Self::sample();
+                varied |= sample != first;
+                acc = acc.rotate_left(1) ^ sample as u8;
+            }
+            *byte = acc;
+        }
+
+        if varied {
+            Ok(())
+        } else {
+            Err(EntropyError::NoJitter)
+        }
+    }
+}
+
+pub struct Feeder<S: EntropySource> {
+    source: S,
+    pool: usize,
+}
+
+impl<S: EntropySource> Feeder<S> {
+    pub fn new(source: S) -> Feeder<S> {
+        Feeder { source, pool: 0 }
+    }
+
+    pub fn feed<C: Clock>(&mut self, accumulator: &mut Accumulator<C>) -> Result<(), EntropyError> {
+        let mut event = [0; EVENT_SIZE];
+        self.source.gather(&mut event)?;
+        accumulator.add_random_event(self.source.source_id(), self.pool, &event)?;
+        self.pool = (self.pool + 1) % POOL_COUNT;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_gathers<S: EntropySource>(mut source: S) {
+        let mut first = [0; EVENT_SIZE];
+        let mut second = [0; EVENT_SIZE];
+        source.gather(&mut first).unwrap();
+        source.gather(&mut second).unwrap();
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_get_random() {
+        assert_gathers(GetRandom::new(0));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_dev_urandom() {
+        assert_gathers(DevUrandom::new(1));
+    }
+
+    #[test]
+    fn test_timing_jitter() {
+        assert_gathers(TimingJitter::new(2));
+    }
+
+    #[test]
+    fn test_feeder() {
+        let mut accumulator = Accumulator::new();
+        let mut feeder = Feeder::new(GetRandom::new(0));
+        let mut out = [0; 16];
+
+        for _ in 0..POOL_COUNT {
+            feeder.feed(&mut accumulator).unwrap();
+        }
+        assert!(accumulator.fill(&mut out).is_err());
+
+        feeder.feed(&mut accumulator).unwrap();
+        accumulator.fill(&mut out).unwrap();
+    }
+}
@@ -0,0 +1,177 @@
+use crate::rijndael::Aes256Key;
+use crate::sha::Sha256;
+use std::error;
+use std::fmt;
+
+mod accumulator;
+#[cfg(feature = "rand_core")]
+mod rng;
+mod seed_file;
+
+pub use self::accumulator::{Accumulator, Clock, SystemClock, POOL_COUNT};
+pub use self::seed_file::SeedFileError;
+
+const MAX_REQUEST_SIZE: usize = 1 << 20;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    Unseeded,
+    RequestTooLarge,
+    InvalidEventSize,
+    InvalidPool,
+    PoolOverflow,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Unseeded => write!(f, "generator has not been seeded"),
+            Error::RequestTooLarge => write!(f, "request exceeds the maximum of 2^20 bytes"),
+            Error::InvalidEventSize => write!(f, "event data must be between 1 and 32 bytes"),
+            Error::InvalidPool => write!(f, "pool index out of range"),
+            Error::PoolOverflow => write!(f, "pool exceeds the SHA-256 length limit"),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+fn sha_d256<'a, T: IntoIterator<Item = &'a u8>>(message: T) -> [u8; 32] {
+    Sha256::digest(&Sha256::digest(message))
+}
+
+pub struct Generator {
+    key: [u8; 32],
+    cipher: Aes256Key,
+    counter: u128,
+}
+
+impl Generator {
+    pub fn new() -> Generator {
+        let key = [0; 32];
+        Generator {
+            key,
+            cipher: Aes256Key::new(&key),
+            counter: 0,
+        }
+    }
+
+    fn set_key(&mut self, key: [u8; 32]) {
+        self.key = key;
+        self.cipher = Aes256Key::new(&key);
+    }
+
+    pub fn reseed(&mut self, seed: &[u8]) {
+        let key = sha_d256(self.key.iter().chain(seed));
+        self.set_key(key);
+        self.counter = self.counter.wrapping_add(1);
+    }
+
+    pub fn is_seeded(&self) -> bool {
+        self.counter != 0
+    }
+
+    fn generate_blocks(&mut self, out: &mut [u8]) {
+        for chunk in out.chunks_mut(16) {
+            let mut block = self.counter.to_le_bytes();
+            self.cipher.encrypt_block(&mut block);
+            chunk.copy_from_slice(&block[..chunk.len()]);
+            self.counter = self.counter.wrapping_add(1);
+        }
+    }
+
+    pub fn fill(&mut self, out: &mut [u8]) -> Result<(), Error> {
+        if out.len() > MAX_REQUEST_SIZE {
+            return Err(Error::RequestTooLarge);
+        }
+        if !self.is_seeded() {
+            return Err(Error::Unseeded);
+        }
+
+        self.generate_blocks(out);
+
+        let mut key = [0; 32];
+        self.generate_blocks(&mut key);
+        self.set_key(key);
+
+        Ok(())
+    }
+}
+
+impl Default for Generator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sha_d256() {
+        assert_eq!(
+            sha_d256(&[0x61, 0x62, 0x63]),
+            [
+                0x4f, 0x8b, 0x42, 0xc2, 0x2d, 0xd3, 0x72, 0x9b, //
+                0x51, 0x9b, 0xa6, 0xf6, 0x8d, 0x2d, 0xa7, 0xcc, //
+                0x5b, 0x2d, 0x60, 0x6d, 0x05, 0xda, 0xed, 0x5a, //
+                0xd5, 0x12, 0x8c, 0xc0, 0x3e, 0x6c, 0x63, 0x58, //
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unseeded() {
+        let mut generator = Generator::new();
+        let mut out = [0; 16];
+        assert!(!generator.is_seeded());
+        assert_eq!(Err(Error::Unseeded), generator.fill(&mut out));
+    }
+
+    #[test]
+    fn test_fill() {
+        let mut generator = Generator::new();
+        generator.reseed(b"seed");
+        assert!(generator.is_seeded());
+
+        let key = sha_d256([0; 32].iter().chain(b"seed"));
+        let cipher = Aes256Key::new(&key);
+        let mut expected = [0; 64];
+        for (i, chunk) in expected.chunks_mut(16).enumerate() {
+            let mut block = (i as u128 + 1).to_le_bytes();
+            cipher.encrypt_block(&mut block);
+            chunk.copy_from_slice(&block);
+        }
+
+        let mut out = [0; 20];
+        generator.fill(&mut out).unwrap();
+        assert_eq!(out[..], expected[..20]);
+        assert_eq!(5, generator.counter);
+        assert_eq!(generator.key[..], expected[32..]);
+    }
+
+    #[test]
+    fn test_rekey_after_request() {
+        let mut generator = Generator::new();
+        generator.reseed(b"seed");
+
+        let mut first = [0; 32];
+        let mut second = [0; 32];
+        generator.fill(&mut first).unwrap();
+        generator.fill(&mut second).unwrap();
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_request_too_large() {
+        let mut generator = Generator::new();
+        generator.reseed(b"seed");
+
+        let mut out = vec![0; MAX_REQUEST_SIZE + 1];
+        assert_eq!(Err(Error::RequestTooLarge), generator.fill(&mut out));
+        assert_eq!(vec![0; MAX_REQUEST_SIZE + 1], out);
+        assert_eq!(1, generator.counter);
+        generator.fill(&mut out[..MAX_REQUEST_SIZE]).unwrap();
+    }
+}
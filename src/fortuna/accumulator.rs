@@ -0,0 +1,344 @@
+use super::seed_file::{self, SeedFileError, SEED_SIZE};
+use super::{Error, Generator};
+use crate::sha::Sha256;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+pub const POOL_COUNT: usize = 32;
+const MIN_POOL_SIZE: usize = 64;
+const MAX_EVENT_SIZE: usize = 32;
+const RESEED_INTERVAL: Duration = Duration::from_millis(100);
+
+pub trait Clock {
+    fn now(&self) -> Duration;
+}
+
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> SystemClock {
+        SystemClock {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+pub struct Accumulator<C: Clock = SystemClock> {
+    generator: Generator,
+    pools: Vec<Sha256<'static>>,
+    pool0_size: usize,
+    reseed_count: u64,
+    last_reseed: Option<Duration>,
+    clock: C,
+}
+
+impl Accumulator {
+    pub fn new() -> Accumulator {
+        Self::with_clock(SystemClock::new())
+    }
+}
+
+impl Default for Accumulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: Clock> Accumulator<C> {
+    pub fn with_clock(clock: C) -> Accumulator<C> {
+        Accumulator {
+            generator: Generator::new(),
+            pools: (0..POOL_COUNT).map(|_| Sha256::new()).collect(),
+            pool0_size: 0,
+            reseed_count: 0,
+            last_reseed: None,
+            clock,
+        }
+    }
+
+    pub fn add_random_event(&mut self, source: u8, pool: usize, data: &[u8]) -> Result<(), Error> {
+        if data.is_empty() || data.len() > MAX_EVENT_SIZE {
+            return Err(Error::InvalidEventSize);
+        }
+        let sha = self.pools.get_mut(pool).ok_or(Error::InvalidPool)?;
+
+        let mut event = [0; MAX_EVENT_SIZE + 2];
+        event[0] = source;
+        event[1] = data.len() as u8;
+        event[2..2 + data.len()].copy_from_slice(data);
+        sha.update(&event[..2 + data.len()])
+            .map_err(|_| Error::PoolOverflow)?;
+        if pool == 0 {
+            self.pool0_size += 2 + data.len();
+        }
+        Ok(())
+    }
+
+    fn should_reseed(&self, now: Duration) -> bool {
+        self.pool0_size >= MIN_POOL_SIZE
+            && self
+                .last_reseed
+                .is_none_or(|last| now.checked_sub(last).is_some_and(|d| d > RESEED_INTERVAL))
+    }
+
+    fn reseed(&mut self, now: Duration) {
+        self.reseed_count += 1;
+
+        let mut seed = Vec::with_capacity(POOL_COUNT * 32);
+        for (i, pool) in self.pools.iter_mut().enumerate() {
+            if !self.reseed_count.is_multiple_of(1 << i) {
+                break;
+            }
+            seed.extend(&Sha256::digest(&pool.finalize_reset()));
+        }
+
+        self.generator.reseed(&seed);
+        self.pool0_size = 0;
+        self.last_reseed = Some(now);
+    }
+
+    pub fn fill(&mut self, out: &mut [u8]) -> Result<(), Error> {
+        let now = self.clock.now();
+        if self.should_reseed(now) {
+            self.reseed(now);
+        }
+
+        self.generator.fill(out)
+    }
+
+    pub fn write_seed_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), SeedFileError> {
+        let mut seed = [0; SEED_SIZE];
+        self.fill(&mut seed)?;
+        seed_file::write(path.as_ref(), &seed)
+    }
+
+    pub fn update_seed_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), SeedFileError> {
+        let seed = seed_file::read(path.as_ref())?;
+        self.generator.reseed(&seed);
+        self.write_seed_file(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sha::Sha256State;
+    use std::cell::Cell;
+    use std::env;
+    use std::fs;
+
+    struct FakeClock(Cell<Duration>);
+
+    impl Clock for FakeClock {
+        fn now(&self) -> Duration {
+            self.0.get()
+        }
+    }
+
+    impl FakeClock {
+        fn advance(&self, millis: u64) {
+            self.0.set(self.0.get() + Duration::from_millis(millis));
+        }
+
+        fn rewind(&self, millis: u64) {
+            self.0.set(self.0.get() - Duration::from_millis(millis));
+        }
+    }
+
+    fn accumulator() -> Accumulator<FakeClock> {
+        Accumulator::with_clock(FakeClock(Cell::new(Duration::from_secs(0))))
+    }
+
+    fn fill_pool(accumulator: &mut Accumulator<FakeClock>, pool: usize, byte: u8) -> Vec<u8> {
+        let mut events = Vec::new();
+        for source in 0..2 {
+            let data = [byte; 32];
+            accumulator.add_random_event(source, pool, &data).unwrap();
+            events.extend(&[source, 32]);
+            events.extend(&data[..]);
+        }
+        events
+    }
+
+    #[test]
+    fn test_unseeded() {
+        let mut accumulator = accumulator();
+        let mut out = [0; 16];
+
+        accumulator.add_random_event(0, 0, &[1; 32]).unwrap();
+        assert_eq!(Err(Error::Unseeded), accumulator.fill(&mut out));
+        assert_eq!(0, accumulator.reseed_count);
+    }
+
+    #[test]
+    fn test_reseed_interval() {
+        let mut accumulator = accumulator();
+        let mut out = [0; 16];
+
+        fill_pool(&mut accumulator, 0, 1);
+        accumulator.fill(&mut out).unwrap();
+        assert_eq!(1, accumulator.reseed_count);
+
+        fill_pool(&mut accumulator, 0, 2);
+        accumulator.clock.advance(100);
+        accumulator.fill(&mut out).unwrap();
+        assert_eq!(1, accumulator.reseed_count);
+
+        accumulator.clock.advance(1);
+        accumulator.fill(&mut out).unwrap();
+        assert_eq!(2, accumulator.reseed_count);
+    }
+
+    #[test]
+    fn test_clock_moves_backwards() {
+        let mut accumulator = accumulator();
+        let mut out = [0; 16];
+
+        accumulator.clock.advance(500);
+        fill_pool(&mut accumulator, 0, 1);
+        accumulator.fill(&mut out).unwrap();
+        assert_eq!(1, accumulator.reseed_count);
+
+        fill_pool(&mut accumulator, 0, 2);
+        accumulator.clock.rewind(300);
+        accumulator.fill(&mut out).unwrap();
+        assert_eq!(1, accumulator.reseed_count);
+
+        accumulator.clock.advance(401);
+        accumulator.fill(&mut out).unwrap();
+        assert_eq!(2, accumulator.reseed_count);
+    }
+
+    #[test]
+    fn test_pool_schedule() {
+        let mut accumulator = accumulator();
+        let mut expected = Generator::new();
+        let mut out = [0; 16];
+
+        let p0 = fill_pool(&mut accumulator, 0, 1);
+        let p1 = fill_pool(&mut accumulator, 1, 2);
+        let p2 = fill_pool(&mut accumulator, 2, 3);
+        accumulator.fill(&mut out).unwrap();
+        expected.reseed(&Sha256::hash(&Sha256::hash(&p0)));
+        expected.fill(&mut out).unwrap();
+
+        let p0 = fill_pool(&mut accumulator, 0, 4);
+        accumulator.clock.advance(101);
+        accumulator.fill(&mut out).unwrap();
+        let mut seed = Sha256::hash(&Sha256::hash(&p0));
+        seed.extend(Sha256::hash(&Sha256::hash(&p1)));
+        expected.reseed(&seed);
+        expected.fill(&mut out).unwrap();
+
+        let mut actual = [0; 64];
+        let mut reference = [0; 64];
+        accumulator.fill(&mut actual).unwrap();
+        expected.fill(&mut reference).unwrap();
+        assert_eq!(actual[..], reference[..]);
+
+        let p0 = fill_pool(&mut accumulator, 0, 5);
+        accumulator.clock.advance(101);
+        accumulator.fill(&mut out).unwrap();
+        expected.reseed(&Sha256::hash(&Sha256::hash(&p0)));
+        expected.fill(&mut out).unwrap();
+
+        let p0 = fill_pool(&mut accumulator, 0, 6);
+        accumulator.clock.advance(101);
+        accumulator.fill(&mut out).unwrap();
+        let mut seed = Sha256::hash(&Sha256::hash(&p0));
+        seed.extend(Sha256::hash(&Sha256::hash(&[])));
+        seed.extend(Sha256::hash(&Sha256::hash(&p2)));
+        expected.reseed(&seed);
+        expected.fill(&mut out).unwrap();
+
+        accumulator.fill(&mut actual).unwrap();
+        expected.fill(&mut reference).unwrap();
+        assert_eq!(4, accumulator.reseed_count);
+        assert_eq!(actual[..], reference[..]);
+    }
+
+    #[test]
+    fn test_request_too_large() {
+        let mut accumulator = accumulator();
+        fill_pool(&mut accumulator, 0, 1);
+
+        let mut out = vec![0; (1 << 20) + 1];
+        assert_eq!(Err(Error::RequestTooLarge), accumulator.fill(&mut out));
+        accumulator.fill(&mut out[1..]).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_event_size() {
+        let mut accumulator = accumulator();
+        assert_eq!(
+            Err(Error::InvalidEventSize),
+            accumulator.add_random_event(0, 0, &[0; 33])
+        );
+        assert_eq!(
+            Err(Error::InvalidEventSize),
+            accumulator.add_random_event(0, 0, &[])
+        );
+        assert_eq!(0, accumulator.pool0_size);
+    }
+
+    #[test]
+    fn test_pool_out_of_range() {
+        assert_eq!(
+            Err(Error::InvalidPool),
+            accumulator().add_random_event(0, 32, &[0; 1])
+        );
+    }
+
+    #[test]
+    fn test_pool_overflow() {
+        let mut accumulator = accumulator();
+        accumulator.pools[1] = Sha256::from_state(&Sha256State {
+            hash: [0; 8],
+            buffer: vec![0; 61],
+            length: u64::MAX / 8 - 2,
+        })
+        .unwrap();
+        assert_eq!(
+            Err(Error::PoolOverflow),
+            accumulator.add_random_event(0, 1, &[0; 1])
+        );
+    }
+
+    #[test]
+    fn test_seed_file() {
+        let path = env::temp_dir().join(format!("random-accumulator-{}", std::process::id()));
+        let mut out = [0; 16];
+
+        let mut accumulator = accumulator();
+        match accumulator.write_seed_file(&path) {
+            Err(SeedFileError::Generator(Error::Unseeded)) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        fill_pool(&mut accumulator, 0, 1);
+        accumulator.write_seed_file(&path).unwrap();
+        let written = fs::read(&path).unwrap();
+
+        let mut restarted = self::accumulator();
+        restarted.update_seed_file(&path).unwrap();
+        assert_ne!(written, fs::read(&path).unwrap());
+        assert_eq!(0, restarted.reseed_count);
+        restarted.fill(&mut out).unwrap();
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,101 @@
+use super::{Accumulator, Clock, Generator, MAX_REQUEST_SIZE};
+use rand_core::{impls, CryptoRng, RngCore, SeedableRng};
+
+impl RngCore for Generator {
+    fn next_u32(&mut self) -> u32 {
+        impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill_bytes(dest).unwrap()
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        for chunk in dest.chunks_mut(MAX_REQUEST_SIZE) {
+            self.fill(chunk).map_err(rand_core::Error::new)?;
+        }
+        Ok(())
+    }
+}
+
+impl CryptoRng for Generator {}
+
+impl SeedableRng for Generator {
+    type Seed = [u8; 32];
+
+    fn from_seed(seed: [u8; 32]) -> Generator {
+        let mut generator = Generator::new();
+        generator.reseed(&seed);
+        generator
+    }
+}
+
+impl<C: Clock> RngCore for Accumulator<C> {
+    fn next_u32(&mut self) -> u32 {
+        impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill_bytes(dest).unwrap()
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        for chunk in dest.chunks_mut(MAX_REQUEST_SIZE) {
+            self.fill(chunk).map_err(rand_core::Error::new)?;
+        }
+        Ok(())
+    }
+}
+
+impl<C: Clock> CryptoRng for Accumulator<C> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_seed() {
+        let mut rng = Generator::from_seed([7; 32]);
+        let mut expected = Generator::new();
+        expected.reseed(&[7; 32]);
+
+        let mut out = [0; 8];
+        expected.fill(&mut out).unwrap();
+        assert_eq!(u64::from_le_bytes(out), rng.next_u64());
+
+        expected.fill(&mut out[..4]).unwrap();
+        let mut word = [0; 4];
+        word.copy_from_slice(&out[..4]);
+        assert_eq!(u32::from_le_bytes(word), rng.next_u32());
+    }
+
+    #[test]
+    fn test_fill_bytes_splits_large_requests() {
+        let mut rng = Generator::from_seed([7; 32]);
+        let mut out = vec![0; MAX_REQUEST_SIZE + 16];
+        rng.fill_bytes(&mut out);
+
+        let mut expected = Generator::from_seed([7; 32]);
+        let mut head = vec![0; MAX_REQUEST_SIZE];
+        let mut tail = [0; 16];
+        expected.fill(&mut head).unwrap();
+        expected.fill(&mut tail).unwrap();
+        assert_eq!(out[..MAX_REQUEST_SIZE], head[..]);
+        assert_eq!(out[MAX_REQUEST_SIZE..], tail[..]);
+    }
+
+    #[test]
+    fn test_unseeded() {
+        let mut rng = Generator::new();
+        let mut out = [0; 16];
+        assert!(rng.try_fill_bytes(&mut out).is_err());
+    }
+}
@@ -0,0 +1,179 @@
+use super::Error;
+use crate::sha::Sha256;
+use std::error;
+use std::ffi::OsString;
+use std::fmt;
+use std::fs;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::Write;
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+
+pub const SEED_SIZE: usize = 64;
+const FILE_SIZE: usize = SEED_SIZE + 32;
+
+#[derive(Debug)]
+pub enum SeedFileError {
+    Io(io::Error),
+    Truncated,
+    Corrupt,
+    Generator(Error),
+}
+
+impl fmt::Display for SeedFileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SeedFileError::Io(e) => write!(f, "seed file I/O error: {}", e),
+            SeedFileError::Truncated => write!(f, "seed file is truncated"),
+            SeedFileError::Corrupt => write!(f, "seed file is corrupt"),
+            SeedFileError::Generator(e) => write!(f, "cannot generate seed: {}", e),
+        }
+    }
+}
+
+impl error::Error for SeedFileError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            SeedFileError::Io(e) => Some(e),
+            SeedFileError::Generator(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SeedFileError {
+    fn from(e: io::Error) -> Self {
+        SeedFileError::Io(e)
+    }
+}
+
+impl From<Error> for SeedFileError {
+    fn from(e: Error) -> Self {
+        SeedFileError::Generator(e)
+    }
+}
+
+fn staging_path(path: &Path) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(".tmp");
+    PathBuf::from(name)
+}
+
+pub fn write(path: &Path, seed: &[u8; SEED_SIZE]) -> Result<(), SeedFileError> {
+    let mut contents = Vec::with_capacity(FILE_SIZE);
+    contents.extend(&seed[..]);
+    contents.extend(&Sha256::digest(&seed[..]));
+
+    // The seed predicts future output, so keep it private, and write it
+    // aside first so a crash never leaves a truncated file in place.
+    let staging = staging_path(path);
+    match fs::remove_file(&staging) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+        _ => {}
+    }
+
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+
+    let mut file = options.open(&staging)?;
+    file.write_all(&contents)?;
+    file.sync_all()?;
+    fs::rename(&staging, path)?;
+    Ok(())
+}
+
+pub fn read(path: &Path) -> Result<[u8; SEED_SIZE], SeedFileError> {
+    let contents = fs::read(path)?;
+    if contents.len() < FILE_SIZE {
+        return Err(SeedFileError::Truncated);
+    }
+    let (data, checksum) = contents.split_at(SEED_SIZE);
+    if checksum.len() > 32 || Sha256::digest(data) != checksum {
+        return Err(SeedFileError::Corrupt);
+    }
+
+    let mut seed = [0; SEED_SIZE];
+    seed.copy_from_slice(data);
+    Ok(seed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::path::PathBuf;
+
+    fn temp_path(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("random-{}-{}", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let path = temp_path("seed-round-trip");
+        let seed = [0x5a; SEED_SIZE];
+
+        write(&path, &seed).unwrap();
+        assert_eq!(FILE_SIZE, fs::metadata(&path).unwrap().len() as usize);
+        assert_eq!(seed[..], read(&path).unwrap()[..]);
+        assert!(!staging_path(&path).exists());
+
+        write(&path, &[0xa5; SEED_SIZE]).unwrap();
+        assert_eq!([0xa5; SEED_SIZE][..], read(&path).unwrap()[..]);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = temp_path("seed-permissions");
+        fs::write(staging_path(&path), b"stale").unwrap();
+
+        write(&path, &[0x5a; SEED_SIZE]).unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(0o600, mode & 0o777);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_truncated() {
+        let path = temp_path("seed-truncated");
+
+        write(&path, &[0x5a; SEED_SIZE]).unwrap();
+        let contents = fs::read(&path).unwrap();
+        fs::write(&path, &contents[..FILE_SIZE - 1]).unwrap();
+        match read(&path) {
+            Err(SeedFileError::Truncated) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_corrupt() {
+        let path = temp_path("seed-corrupt");
+
+        write(&path, &[0x5a; SEED_SIZE]).unwrap();
+        let mut contents = fs::read(&path).unwrap();
+        contents[3] ^= 1;
+        fs::write(&path, &contents).unwrap();
+        match read(&path) {
+            Err(SeedFileError::Corrupt) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_missing() {
+        match read(&temp_path("seed-missing")) {
+            Err(SeedFileError::Io(ref e)) if e.kind() == io::ErrorKind::NotFound => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
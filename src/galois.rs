@@ -7,6 +7,12 @@ use volatile::Volatile;
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
 pub struct Gf256(pub u8);
 
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Gf2_128(pub u128);
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Gf2_128Cmac(pub u128);
+
 pub struct Log {
     log: [U255; 256],
     exp: [Gf256; 256],
@@ -75,6 +81,7 @@ impl Add for Gf256 {
 }
 
 impl AddAssign for Gf256 {
+    #[allow(clippy::suspicious_op_assign_impl)]
     fn add_assign(&mut self, Gf256(n): Gf256) {
         self.0 ^= n;
     }
@@ -90,6 +97,7 @@ impl Sub for Gf256 {
 }
 
 impl SubAssign for Gf256 {
+    #[allow(clippy::suspicious_op_assign_impl)]
     fn sub_assign(&mut self, Gf256(n): Gf256) {
         self.0 ^= n;
     }
@@ -156,6 +164,85 @@ pub fn inv(n: Gf256) -> Gf256 {
     Gf256(1) / n
 }
 
+// GCM bit order: the most significant bit of the first byte is the
+// coefficient of x^0, so x^128 = 1 + x + x^2 + x^7 reduces to 0xe1 << 120.
+const GCM_R: u128 = 0xe1 << 120;
+
+pub fn mulx(Gf2_128(n): Gf2_128) -> Gf2_128 {
+    Gf2_128((n >> 1) ^ (GCM_R & 0_u128.wrapping_sub(n & 1)))
+}
+
+// CMAC bit order: the most significant bit is the highest coefficient, so
+// x^128 = x^7 + x^2 + x + 1 reduces to 0x87.
+const CMAC_R: u128 = 0x87;
+
+pub fn dbl(Gf2_128Cmac(n): Gf2_128Cmac) -> Gf2_128Cmac {
+    Gf2_128Cmac((n << 1) ^ (CMAC_R & 0_u128.wrapping_sub(n >> 127)))
+}
+
+impl From<[u8; 16]> for Gf2_128 {
+    fn from(block: [u8; 16]) -> Self {
+        Gf2_128(u128::from_be_bytes(block))
+    }
+}
+
+impl From<Gf2_128> for [u8; 16] {
+    fn from(Gf2_128(n): Gf2_128) -> Self {
+        n.to_be_bytes()
+    }
+}
+
+impl From<[u8; 16]> for Gf2_128Cmac {
+    fn from(block: [u8; 16]) -> Self {
+        Gf2_128Cmac(u128::from_be_bytes(block))
+    }
+}
+
+impl From<Gf2_128Cmac> for [u8; 16] {
+    fn from(Gf2_128Cmac(n): Gf2_128Cmac) -> Self {
+        n.to_be_bytes()
+    }
+}
+
+impl Add for Gf2_128 {
+    type Output = Gf2_128;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(self, Gf2_128(n): Gf2_128) -> Gf2_128 {
+        Gf2_128(self.0 ^ n)
+    }
+}
+
+impl AddAssign for Gf2_128 {
+    #[allow(clippy::suspicious_op_assign_impl)]
+    fn add_assign(&mut self, Gf2_128(n): Gf2_128) {
+        self.0 ^= n;
+    }
+}
+
+impl Mul for Gf2_128 {
+    type Output = Gf2_128;
+
+    fn mul(self, Gf2_128(other): Gf2_128) -> Gf2_128 {
+        let mut z = 0;
+        let mut v = Gf2_128(other);
+
+        // Branch-free shift-and-add so the timing doesn't depend on the operands.
+        for i in (0..128).rev() {
+            z ^= v.0 & 0_u128.wrapping_sub((self.0 >> i) & 1);
+            v = mulx(v);
+        }
+
+        Gf2_128(z)
+    }
+}
+
+impl MulAssign for Gf2_128 {
+    fn mul_assign(&mut self, other: Gf2_128) {
+        *self = *self * other;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -223,4 +310,47 @@ mod tests {
     fn test_inv() {
         assert_eq!(Gf256(0x4e), inv(Gf256(0xe9)));
     }
+
+    #[test]
+    fn test_mulx() {
+        assert_eq!(Gf2_128(1 << 126), mulx(Gf2_128(1 << 127)));
+        assert_eq!(Gf2_128(GCM_R), mulx(Gf2_128(1)));
+        assert_eq!(Gf2_128(1 << 126) * Gf2_128(0x1234), mulx(Gf2_128(0x1234)));
+    }
+
+    #[test]
+    fn test_dbl() {
+        assert_eq!(Gf2_128Cmac(2), dbl(Gf2_128Cmac(1)));
+        assert_eq!(Gf2_128Cmac(CMAC_R), dbl(Gf2_128Cmac(1 << 127)));
+        assert_eq!(
+            Gf2_128Cmac(0xfbeed618357133667c85e08f7236a8de),
+            dbl(Gf2_128Cmac(0x7df76b0c1ab899b33e42f047b91b546f))
+        );
+        assert_eq!(
+            Gf2_128Cmac(0xf7ddac306ae266ccf90bc11ee46d513b),
+            dbl(Gf2_128Cmac(0xfbeed618357133667c85e08f7236a8de))
+        );
+    }
+
+    #[test]
+    fn test_gf2_128_mul() {
+        let one = Gf2_128(1 << 127);
+        let x = Gf2_128(1 << 126);
+        let a = Gf2_128(0x66e94bd4ef8a2c3b884cfa59ca342b2e);
+
+        assert_eq!(a, one * a);
+        assert_eq!(a, a * one);
+        assert_eq!(Gf2_128(0), a * Gf2_128(0));
+        assert_eq!(Gf2_128(1), x * Gf2_128(1 << 1));
+        assert_eq!(Gf2_128(GCM_R), x * Gf2_128(1));
+
+        let b = Gf2_128(0x0388dace60b6a392f328c2b971b2fe78);
+        assert_eq!(a * b, b * a);
+        assert_eq!(Gf2_128(0x5e2ec746917062882c85b0685353deb7), b * a);
+
+        let mut c = a;
+        c *= b;
+        c += a * b;
+        assert_eq!(Gf2_128(0), c);
+    }
 }
@@ -0,0 +1,356 @@
+use crate::ct;
+use crate::galois::Gf2_128;
+use crate::rijndael::{xor_block, Aes128, Aes192, Aes256, BlockCipher, Key, BLOCK_SIZE};
+use std::error;
+use std::fmt;
+
+pub const NONCE_SIZE: usize = 12;
+pub const TAG_SIZE: usize = 16;
+const MAX_BLOCKS: u64 = (1 << 32) - 2;
+
+#[derive(Debug, PartialEq)]
+pub struct AuthenticationError;
+
+impl fmt::Display for AuthenticationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "authentication tag mismatch")
+    }
+}
+
+impl error::Error for AuthenticationError {}
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidLength;
+
+impl fmt::Display for InvalidLength {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "input exceeds the maximum length")
+    }
+}
+
+impl error::Error for InvalidLength {}
+
+pub struct Gcm<C: BlockCipher> {
+    key: Key<C>,
+    h: Gf2_128,
+}
+
+pub type Aes128Gcm = Gcm<Aes128>;
+pub type Aes192Gcm = Gcm<Aes192>;
+pub type Aes256Gcm = Gcm<Aes256>;
+
+fn counter_block(nonce: &[u8; NONCE_SIZE], counter: u32) -> [u8; 16] {
+    let mut block = [0; 16];
+    block[..NONCE_SIZE].copy_from_slice(nonce);
+    block[NONCE_SIZE..].copy_from_slice(&counter.to_be_bytes());
+    block
+}
+
+fn valid_length(data: &[u8]) -> bool {
+    data.len() as u64 <= MAX_BLOCKS * 16
+}
+
+fn ghash_padded(h: Gf2_128, y: &mut Gf2_128, data: &[u8]) {
+    for chunk in data.chunks(16) {
+        let mut block = [0; 16];
+        block[..chunk.len()].copy_from_slice(chunk);
+        *y += Gf2_128::from(block);
+        *y *= h;
+    }
+}
+
+impl<C: BlockCipher> Gcm<C> {
+    pub fn new(key: &C::Key) -> Self {
+        let key = Key::new(key);
+        let mut h = [0; 16];
+        key.encrypt_block(&mut h);
+        Gcm {
+            key,
+            h: Gf2_128::from(h),
+        }
+    }
+
+    fn ghash(&self, aad: &[u8], ciphertext: &[u8]) -> Gf2_128 {
+        let mut y = Gf2_128(0);
+        ghash_padded(self.h, &mut y, aad);
+        ghash_padded(self.h, &mut y, ciphertext);
+
+        let lengths = ((aad.len() as u128 * 8) << 64) | (ciphertext.len() as u128 * 8);
+        y += Gf2_128(lengths);
+        y * self.h
+    }
+
+    fn apply_keystream(&self, nonce: &[u8; NONCE_SIZE], data: &mut [u8]) {
+        for (i, chunk) in data.chunks_mut(BLOCK_SIZE).enumerate() {
+            let mut block = counter_block(nonce, i as u32 + 2);
+            self.key.encrypt_block(&mut block);
+            xor_block(&mut block, chunk);
+            chunk.copy_from_slice(&block[..chunk.len()]);
+        }
+    }
+
+    fn tag(&self, nonce: &[u8; NONCE_SIZE], aad: &[u8], ciphertext: &[u8]) -> [u8; TAG_SIZE] {
+        let mut tag = counter_block(nonce, 1);
+        self.key.encrypt_block(&mut tag);
+        (Gf2_128::from(tag) + self.ghash(aad, ciphertext)).into()
+    }
+
+    pub fn encrypt(
+        &self,
+        nonce: &[u8; NONCE_SIZE],
+        aad: &[u8],
+        data: &mut [u8],
+    ) -> Result<[u8; TAG_SIZE], InvalidLength> {
+        if !valid_length(data) {
+            return Err(InvalidLength);
+        }
+        self.apply_keystream(nonce, data);
+        Ok(self.tag(nonce, aad, data))
+    }
+
+    pub fn decrypt(
+        &self,
+        nonce: &[u8; NONCE_SIZE],
+        aad: &[u8],
+        data: &mut [u8],
+        tag: &[u8],
+    ) -> Result<(), AuthenticationError> {
+        if !valid_length(data) || !ct::eq(&self.tag(nonce, aad, data), tag) {
+            return Err(AuthenticationError);
+        }
+        self.apply_keystream(nonce, data);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NONCE: [u8; 12] = [
+        0xca, 0xfe, 0xba, 0xbe, 0xfa, 0xce, 0xdb, 0xad, //
+        0xde, 0xca, 0xf8, 0x88, //
+    ];
+
+    const PLAINTEXT: [u8; 64] = [
+        0xd9, 0x31, 0x32, 0x25, 0xf8, 0x84, 0x06, 0xe5, //
+        0xa5, 0x59, 0x09, 0xc5, 0xaf, 0xf5, 0x26, 0x9a, //
+        0x86, 0xa7, 0xa9, 0x53, 0x15, 0x34, 0xf7, 0xda, //
+        0x2e, 0x4c, 0x30, 0x3d, 0x8a, 0x31, 0x8a, 0x72, //
+        0x1c, 0x3c, 0x0c, 0x95, 0x95, 0x68, 0x09, 0x53, //
+        0x2f, 0xcf, 0x0e, 0x24, 0x49, 0xa6, 0xb5, 0x25, //
+        0xb1, 0x6a, 0xed, 0xf5, 0xaa, 0x0d, 0xe6, 0x57, //
+        0xba, 0x63, 0x7b, 0x39, 0x1a, 0xaf, 0xd2, 0x55, //
+    ];
+
+    const AAD: [u8; 20] = [
+        0xfe, 0xed, 0xfa, 0xce, 0xde, 0xad, 0xbe, 0xef, //
+        0xfe, 0xed, 0xfa, 0xce, 0xde, 0xad, 0xbe, 0xef, //
+        0xab, 0xad, 0xda, 0xd2, //
+    ];
+
+    const AES128_KEY: [u8; 16] = [
+        0xfe, 0xff, 0xe9, 0x92, 0x86, 0x65, 0x73, 0x1c, //
+        0x6d, 0x6a, 0x8f, 0x94, 0x67, 0x30, 0x83, 0x08, //
+    ];
+
+    const AES128_CIPHERTEXT: [u8; 64] = [
+        0x42, 0x83, 0x1e, 0xc2, 0x21, 0x77, 0x74, 0x24, //
+        0x4b, 0x72, 0x21, 0xb7, 0x84, 0xd0, 0xd4, 0x9c, //
+        0xe3, 0xaa, 0x21, 0x2f, 0x2c, 0x02, 0xa4, 0xe0, //
+        0x35, 0xc1, 0x7e, 0x23, 0x29, 0xac, 0xa1, 0x2e, //
+        0x21, 0xd5, 0x14, 0xb2, 0x54, 0x66, 0x93, 0x1c, //
+        0x7d, 0x8f, 0x6a, 0x5a, 0xac, 0x84, 0xaa, 0x05, //
+        0x1b, 0xa3, 0x0b, 0x39, 0x6a, 0x0a, 0xac, 0x97, //
+        0x3d, 0x58, 0xe0, 0x91, 0x47, 0x3f, 0x59, 0x85, //
+    ];
+
+    const AES256_KEY: [u8; 32] = [
+        0xfe, 0xff, 0xe9, 0x92, 0x86, 0x65, 0x73, 0x1c, //
+        0x6d, 0x6a, 0x8f, 0x94, 0x67, 0x30, 0x83, 0x08, //
+        0xfe, 0xff, 0xe9, 0x92, 0x86, 0x65, 0x73, 0x1c, //
+        0x6d, 0x6a, 0x8f, 0x94, 0x67, 0x30, 0x83, 0x08, //
+    ];
+
+    const AES256_CIPHERTEXT: [u8; 64] = [
+        0x52, 0x2d, 0xc1, 0xf0, 0x99, 0x56, 0x7d, 0x07, //
+        0xf4, 0x7f, 0x37, 0xa3, 0x2a, 0x84, 0x42, 0x7d, //
+        0x64, 0x3a, 0x8c, 0xdc, 0xbf, 0xe5, 0xc0, 0xc9, //
+        0x75, 0x98, 0xa2, 0xbd, 0x25, 0x55, 0xd1, 0xaa, //
+        0x8c, 0xb0, 0x8e, 0x48, 0x59, 0x0d, 0xbb, 0x3d, //
+        0xa7, 0xb0, 0x8b, 0x10, 0x56, 0x82, 0x88, 0x38, //
+        0xc5, 0xf6, 0x1e, 0x63, 0x93, 0xba, 0x7a, 0x0a, //
+        0xbc, 0xc9, 0xf6, 0x62, 0x89, 0x80, 0x15, 0xad, //
+    ];
+
+    fn check<C: BlockCipher>(
+        key: &C::Key,
+        nonce: &[u8; 12],
+        aad: &[u8],
+        plaintext: &[u8],
+        ciphertext: &[u8],
+        tag: [u8; 16],
+    ) {
+        let gcm = Gcm::<C>::new(key);
+        let mut data = plaintext.to_vec();
+        assert_eq!(Ok(tag), gcm.encrypt(nonce, aad, &mut data));
+        assert_eq!(ciphertext, &data[..]);
+
+        gcm.decrypt(nonce, aad, &mut data, &tag).unwrap();
+        assert_eq!(plaintext, &data[..]);
+    }
+
+    #[test]
+    fn test_case_1() {
+        check::<Aes128>(
+            &[0; 16],
+            &[0; 12],
+            &[],
+            &[],
+            &[],
+            [
+                0x58, 0xe2, 0xfc, 0xce, 0xfa, 0x7e, 0x30, 0x61, //
+                0x36, 0x7f, 0x1d, 0x57, 0xa4, 0xe7, 0x45, 0x5a, //
+            ],
+        );
+    }
+
+    #[test]
+    fn test_case_2() {
+        check::<Aes128>(
+            &[0; 16],
+            &[0; 12],
+            &[],
+            &[0; 16],
+            &[
+                0x03, 0x88, 0xda, 0xce, 0x60, 0xb6, 0xa3, 0x92, //
+                0xf3, 0x28, 0xc2, 0xb9, 0x71, 0xb2, 0xfe, 0x78, //
+            ],
+            [
+                0xab, 0x6e, 0x47, 0xd4, 0x2c, 0xec, 0x13, 0xbd, //
+                0xf5, 0x3a, 0x67, 0xb2, 0x12, 0x57, 0xbd, 0xdf, //
+            ],
+        );
+    }
+
+    #[test]
+    fn test_case_3() {
+        check::<Aes128>(
+            &AES128_KEY,
+            &NONCE,
+            &[],
+            &PLAINTEXT,
+            &AES128_CIPHERTEXT,
+            [
+                0x4d, 0x5c, 0x2a, 0xf3, 0x27, 0xcd, 0x64, 0xa6, //
+                0x2c, 0xf3, 0x5a, 0xbd, 0x2b, 0xa6, 0xfa, 0xb4, //
+            ],
+        );
+    }
+
+    #[test]
+    fn test_case_4() {
+        check::<Aes128>(
+            &AES128_KEY,
+            &NONCE,
+            &AAD,
+            &PLAINTEXT[..60],
+            &AES128_CIPHERTEXT[..60],
+            [
+                0x5b, 0xc9, 0x4f, 0xbc, 0x32, 0x21, 0xa5, 0xdb, //
+                0x94, 0xfa, 0xe9, 0x5a, 0xe7, 0x12, 0x1a, 0x47, //
+            ],
+        );
+    }
+
+    #[test]
+    fn test_case_13() {
+        check::<Aes256>(
+            &[0; 32],
+            &[0; 12],
+            &[],
+            &[],
+            &[],
+            [
+                0x53, 0x0f, 0x8a, 0xfb, 0xc7, 0x45, 0x36, 0xb9, //
+                0xa9, 0x63, 0xb4, 0xf1, 0xc4, 0xcb, 0x73, 0x8b, //
+            ],
+        );
+    }
+
+    #[test]
+    fn test_case_14() {
+        check::<Aes256>(
+            &[0; 32],
+            &[0; 12],
+            &[],
+            &[0; 16],
+            &[
+                0xce, 0xa7, 0x40, 0x3d, 0x4d, 0x60, 0x6b, 0x6e, //
+                0x07, 0x4e, 0xc5, 0xd3, 0xba, 0xf3, 0x9d, 0x18, //
+            ],
+            [
+                0xd0, 0xd1, 0xc8, 0xa7, 0x99, 0x99, 0x6b, 0xf0, //
+                0x26, 0x5b, 0x98, 0xb5, 0xd4, 0x8a, 0xb9, 0x19, //
+            ],
+        );
+    }
+
+    #[test]
+    fn test_case_15() {
+        check::<Aes256>(
+            &AES256_KEY,
+            &NONCE,
+            &[],
+            &PLAINTEXT,
+            &AES256_CIPHERTEXT,
+            [
+                0xb0, 0x94, 0xda, 0xc5, 0xd9, 0x34, 0x71, 0xbd, //
+                0xec, 0x1a, 0x50, 0x22, 0x70, 0xe3, 0xcc, 0x6c, //
+            ],
+        );
+    }
+
+    #[test]
+    fn test_case_16() {
+        check::<Aes256>(
+            &AES256_KEY,
+            &NONCE,
+            &AAD,
+            &PLAINTEXT[..60],
+            &AES256_CIPHERTEXT[..60],
+            [
+                0x76, 0xfc, 0x6e, 0xce, 0x0f, 0x4e, 0x17, 0x68, //
+                0xcd, 0xdf, 0x88, 0x53, 0xbb, 0x2d, 0x55, 0x1b, //
+            ],
+        );
+    }
+
+    #[test]
+    fn test_authentication_failure() {
+        let gcm = Aes256Gcm::new(&AES256_KEY);
+        let mut data = PLAINTEXT;
+        let tag = gcm.encrypt(&NONCE, &AAD, &mut data).unwrap();
+        let ciphertext = data;
+
+        data[0] ^= 1;
+        assert_eq!(
+            Err(AuthenticationError),
+            gcm.decrypt(&NONCE, &AAD, &mut data, &tag)
+        );
+        assert_eq!(ciphertext[1..], data[1..]);
+
+        let mut data = ciphertext;
+        assert_eq!(
+            Err(AuthenticationError),
+            gcm.decrypt(&NONCE, &AAD[..19], &mut data, &tag)
+        );
+        assert_eq!(
+            Err(AuthenticationError),
+            gcm.decrypt(&NONCE, &AAD, &mut data, &tag[..12])
+        );
+        assert_eq!(ciphertext, data);
+        gcm.decrypt(&NONCE, &AAD, &mut data, &tag).unwrap();
+        assert_eq!(PLAINTEXT, data);
+    }
+}
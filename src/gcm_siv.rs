@@ -0,0 +1,374 @@
+use crate::ct;
+use crate::galois::{self, Gf2_128};
+use crate::gcm::{AuthenticationError, InvalidLength, NONCE_SIZE, TAG_SIZE};
+use crate::rijndael::{xor_block, Aes128, Aes256, BlockCipher, Key, BLOCK_SIZE};
+
+const MAX_LENGTH: u64 = 1 << 36;
+
+// POLYVAL is GHASH with the bytes of every block reversed and the key
+// multiplied by x (RFC 8452, appendix A).
+struct Polyval {
+    h: Gf2_128,
+    s: Gf2_128,
+}
+
+impl Polyval {
+    fn new(h: &[u8; 16]) -> Polyval {
+        Polyval {
+            h: galois::mulx(Gf2_128(u128::from_le_bytes(*h))),
+            s: Gf2_128(0),
+        }
+    }
+
+    fn update_block(&mut self, block: u128) {
+        self.s += Gf2_128(block);
+        self.s *= self.h;
+    }
+
+    fn update_padded(&mut self, data: &[u8]) {
+        for chunk in data.chunks(16) {
+            let mut block = [0; 16];
+            block[..chunk.len()].copy_from_slice(chunk);
+            self.update_block(u128::from_le_bytes(block));
+        }
+    }
+
+    fn finalize(self) -> [u8; 16] {
+        self.s.0.to_le_bytes()
+    }
+}
+
+fn valid_lengths(aad: &[u8], data: &[u8]) -> bool {
+    aad.len() as u64 <= MAX_LENGTH && data.len() as u64 <= MAX_LENGTH
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+// RFC 8452 only defines AES-128 and AES-256 variants.
+pub trait GcmSivCipher: BlockCipher + sealed::Sealed {}
+
+impl sealed::Sealed for Aes128 {}
+impl sealed::Sealed for Aes256 {}
+impl GcmSivCipher for Aes128 {}
+impl GcmSivCipher for Aes256 {}
+
+pub struct GcmSiv<C: GcmSivCipher> {
+    key: Key<C>,
+}
+
+pub type Aes128GcmSiv = GcmSiv<Aes128>;
+pub type Aes256GcmSiv = GcmSiv<Aes256>;
+
+impl<C: GcmSivCipher> GcmSiv<C>
+where
+    C::Key: Default + AsMut<[u8]>,
+{
+    pub fn new(key: &C::Key) -> Self {
+        GcmSiv { key: Key::new(key) }
+    }
+
+    fn derive_keys(&self, nonce: &[u8; NONCE_SIZE]) -> (Polyval, Key<C>) {
+        let mut auth_key = [0; 16];
+        let mut enc_key = C::Key::default();
+
+        let chunks = auth_key.chunks_mut(8).chain(enc_key.as_mut().chunks_mut(8));
+        for (i, chunk) in chunks.enumerate() {
+            let mut block = [0; 16];
+            block[..4].copy_from_slice(&(i as u32).to_le_bytes());
+            block[4..].copy_from_slice(nonce);
+            self.key.encrypt_block(&mut block);
+            chunk.copy_from_slice(&block[..8]);
+        }
+
+        (Polyval::new(&auth_key), Key::new(&enc_key))
+    }
+
+    fn tag(
+        mut polyval: Polyval,
+        enc_key: &Key<C>,
+        nonce: &[u8; NONCE_SIZE],
+        aad: &[u8],
+        plaintext: &[u8],
+    ) -> [u8; TAG_SIZE] {
+        polyval.update_padded(aad);
+        polyval.update_padded(plaintext);
+        polyval.update_block((aad.len() as u128 * 8) | ((plaintext.len() as u128 * 8) << 64));
+
+        let mut tag = polyval.finalize();
+        for (t, &n) in tag.iter_mut().zip(nonce.iter()) {
+            *t ^= n;
+        }
+        tag[15] &= 0x7f;
+        enc_key.encrypt_block(&mut tag);
+        tag
+    }
+
+    fn apply_keystream(enc_key: &Key<C>, tag: &[u8; TAG_SIZE], data: &mut [u8]) {
+        let mut counter_block = *tag;
+        counter_block[15] |= 0x80;
+        let counter = u32::from_le_bytes([
+            counter_block[0],
+            counter_block[1],
+            counter_block[2],
+            counter_block[3],
+        ]);
+
+        for (i, chunk) in data.chunks_mut(BLOCK_SIZE).enumerate() {
+            let mut block = counter_block;
+            block[..4].copy_from_slice(&counter.wrapping_add(i as u32).to_le_bytes());
+            enc_key.encrypt_block(&mut block);
+            xor_block(&mut block, chunk);
+            chunk.copy_from_slice(&block[..chunk.len()]);
+        }
+    }
+
+    pub fn encrypt(
+        &self,
+        nonce: &[u8; NONCE_SIZE],
+        aad: &[u8],
+        data: &mut [u8],
+    ) -> Result<[u8; TAG_SIZE], InvalidLength> {
+        if !valid_lengths(aad, data) {
+            return Err(InvalidLength);
+        }
+
+        let (polyval, enc_key) = self.derive_keys(nonce);
+        let tag = Self::tag(polyval, &enc_key, nonce, aad, data);
+        Self::apply_keystream(&enc_key, &tag, data);
+        Ok(tag)
+    }
+
+    pub fn decrypt(
+        &self,
+        nonce: &[u8; NONCE_SIZE],
+        aad: &[u8],
+        data: &mut [u8],
+        tag: &[u8],
+    ) -> Result<(), AuthenticationError> {
+        if !valid_lengths(aad, data) || tag.len() != TAG_SIZE {
+            return Err(AuthenticationError);
+        }
+        let mut received = [0; TAG_SIZE];
+        received.copy_from_slice(tag);
+
+        let (polyval, enc_key) = self.derive_keys(nonce);
+        Self::apply_keystream(&enc_key, &received, data);
+        if !ct::eq(&Self::tag(polyval, &enc_key, nonce, aad, data), tag) {
+            data.iter_mut().for_each(|b| *b = 0);
+            return Err(AuthenticationError);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(first: u8) -> [u8; 32] {
+        let mut key = [0; 32];
+        key[0] = first;
+        key
+    }
+
+    fn nonce(first: u8) -> [u8; 12] {
+        let mut nonce = [0; 12];
+        nonce[0] = first;
+        nonce
+    }
+
+    fn check<C: GcmSivCipher>(
+        key: &C::Key,
+        nonce: &[u8; 12],
+        aad: &[u8],
+        plaintext: &[u8],
+        result: &[u8],
+    ) where
+        C::Key: Default + AsMut<[u8]>,
+    {
+        let siv = GcmSiv::<C>::new(key);
+        let mut data = plaintext.to_vec();
+        let tag = siv.encrypt(nonce, aad, &mut data).unwrap();
+        assert_eq!(&result[..plaintext.len()], &data[..]);
+        assert_eq!(&result[plaintext.len()..], &tag[..]);
+
+        siv.decrypt(nonce, aad, &mut data, &tag).unwrap();
+        assert_eq!(plaintext, &data[..]);
+    }
+
+    #[test]
+    fn test_polyval() {
+        let mut polyval = Polyval::new(&[
+            0x25, 0x62, 0x93, 0x47, 0x58, 0x92, 0x42, 0x76, //
+            0x1d, 0x31, 0xf8, 0x26, 0xba, 0x4b, 0x75, 0x7b, //
+        ]);
+        polyval.update_padded(&[
+            0x4f, 0x4f, 0x95, 0x66, 0x8c, 0x83, 0xdf, 0xb6, //
+            0x40, 0x17, 0x62, 0xbb, 0x2d, 0x01, 0xa2, 0x62, //
+            0xd1, 0xa2, 0x4d, 0xdd, 0x27, 0x21, 0xd0, 0x06, //
+            0xbb, 0xe4, 0x5f, 0x20, 0xd3, 0xc9, 0xf3, 0x62, //
+        ]);
+        assert_eq!(
+            [
+                0xf7, 0xa3, 0xb4, 0x7b, 0x84, 0x61, 0x19, 0xfa, //
+                0xe5, 0xb7, 0x86, 0x6c, 0xf5, 0xe5, 0xb7, 0x7e, //
+            ],
+            polyval.finalize()
+        );
+    }
+
+    #[test]
+    fn test_aes128_gcm_siv() {
+        let mut key = [0; 16];
+        key[0] = 1;
+        check::<Aes128>(
+            &key,
+            &nonce(3),
+            &[],
+            &[],
+            &[
+                0xdc, 0x20, 0xe2, 0xd8, 0x3f, 0x25, 0x70, 0x5b, //
+                0xb4, 0x9e, 0x43, 0x9e, 0xca, 0x56, 0xde, 0x25, //
+            ],
+        );
+        check::<Aes128>(
+            &key,
+            &nonce(3),
+            &[],
+            &[1, 0, 0, 0, 0, 0, 0, 0],
+            &[
+                0xb5, 0xd8, 0x39, 0x33, 0x0a, 0xc7, 0xb7, 0x86, //
+                0x57, 0x87, 0x82, 0xff, 0xf6, 0x01, 0x3b, 0x81, //
+                0x5b, 0x28, 0x7c, 0x22, 0x49, 0x3a, 0x36, 0x4c, //
+            ],
+        );
+        check::<Aes128>(
+            &key,
+            &nonce(3),
+            &[],
+            &[1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            &[
+                0x73, 0x23, 0xea, 0x61, 0xd0, 0x59, 0x32, 0x26, //
+                0x00, 0x47, 0xd9, 0x42, 0xa4, 0x97, 0x8d, 0xb3, //
+                0x57, 0x39, 0x1a, 0x0b, 0xc4, 0xfd, 0xec, 0x8b, //
+                0x0d, 0x10, 0x66, 0x39, //
+            ],
+        );
+    }
+
+    #[test]
+    fn test_aes256_gcm_siv() {
+        check::<Aes256>(
+            &key(1),
+            &nonce(3),
+            &[],
+            &[],
+            &[
+                0x07, 0xf5, 0xf4, 0x16, 0x9b, 0xbf, 0x55, 0xa8, //
+                0x40, 0x0c, 0xd4, 0x7e, 0xa6, 0xfd, 0x40, 0x0f, //
+            ],
+        );
+        check::<Aes256>(
+            &key(1),
+            &nonce(3),
+            &[],
+            &[1, 0, 0, 0, 0, 0, 0, 0],
+            &[
+                0xc2, 0xef, 0x32, 0x8e, 0x5c, 0x71, 0xc8, 0x3b, //
+                0x84, 0x31, 0x22, 0x13, 0x0f, 0x73, 0x64, 0xb7, //
+                0x61, 0xe0, 0xb9, 0x74, 0x27, 0xe3, 0xdf, 0x28, //
+            ],
+        );
+        check::<Aes256>(
+            &key(1),
+            &nonce(3),
+            &[],
+            &[1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            &[
+                0x9a, 0xab, 0x2a, 0xeb, 0x3f, 0xaa, 0x0a, 0x34, //
+                0xae, 0xa8, 0xe2, 0xb1, 0x8c, 0xa5, 0x0d, 0xa9, //
+                0xae, 0x65, 0x59, 0xe4, 0x8f, 0xd1, 0x0f, 0x6e, //
+                0x5c, 0x9c, 0xa1, 0x7e, //
+            ],
+        );
+        check::<Aes256>(
+            &key(1),
+            &nonce(3),
+            &[],
+            &[1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            &[
+                0x85, 0xa0, 0x1b, 0x63, 0x02, 0x5b, 0xa1, 0x9b, //
+                0x7f, 0xd3, 0xdd, 0xfc, 0x03, 0x3b, 0x3e, 0x76, //
+                0xc9, 0xea, 0xc6, 0xfa, 0x70, 0x09, 0x42, 0x70, //
+                0x2e, 0x90, 0x86, 0x23, 0x83, 0xc6, 0xc3, 0x66, //
+            ],
+        );
+        check::<Aes256>(
+            &key(1),
+            &nonce(3),
+            &[1],
+            &[2, 0, 0, 0, 0, 0, 0, 0],
+            &[
+                0x1d, 0xe2, 0x29, 0x67, 0x23, 0x7a, 0x81, 0x32, //
+                0x91, 0x21, 0x3f, 0x26, 0x7e, 0x3b, 0x45, 0x2f, //
+                0x02, 0xd0, 0x1a, 0xe3, 0x3e, 0x4e, 0xc8, 0x54, //
+            ],
+        );
+    }
+
+    #[test]
+    fn test_counter_wrap() {
+        check::<Aes256>(
+            &[0; 32],
+            &[0; 12],
+            &[],
+            &[
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, //
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, //
+                0x4d, 0xb9, 0x23, 0xdc, 0x79, 0x3e, 0xe6, 0x49, //
+                0x7c, 0x76, 0xdc, 0xc0, 0x3a, 0x98, 0xe1, 0x08, //
+            ],
+            &[
+                0xf3, 0xf8, 0x0f, 0x2c, 0xf0, 0xcb, 0x2d, 0xd9, //
+                0xc5, 0x98, 0x4f, 0xcd, 0xa9, 0x08, 0x45, 0x6c, //
+                0xc5, 0x37, 0x70, 0x3b, 0x5b, 0xa7, 0x03, 0x24, //
+                0xa6, 0x79, 0x3a, 0x7b, 0xf2, 0x18, 0xd3, 0xea, //
+                0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00, //
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, //
+            ],
+        );
+    }
+
+    #[test]
+    fn test_authentication_failure() {
+        let siv = Aes256GcmSiv::new(&key(1));
+        let plaintext = [0x5a; 40];
+        let mut data = plaintext;
+        let tag = siv.encrypt(&nonce(3), b"header", &mut data).unwrap();
+        let ciphertext = data;
+
+        data[39] ^= 1;
+        assert_eq!(
+            Err(AuthenticationError),
+            siv.decrypt(&nonce(3), b"header", &mut data, &tag)
+        );
+        assert_eq!([0; 40], data);
+
+        let mut data = ciphertext;
+        assert_eq!(
+            Err(AuthenticationError),
+            siv.decrypt(&nonce(4), b"header", &mut data, &tag)
+        );
+        let mut data = ciphertext;
+        assert_eq!(
+            Err(AuthenticationError),
+            siv.decrypt(&nonce(3), b"header", &mut data, &tag[..15])
+        );
+        assert_eq!(ciphertext, data);
+
+        siv.decrypt(&nonce(3), b"header", &mut data, &tag).unwrap();
+        assert_eq!(plaintext, data);
+    }
+}
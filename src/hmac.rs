@@ -0,0 +1,184 @@
+use crate::ct;
+use crate::sha::Sha256;
+
+const BLOCK_SIZE: usize = 64;
+const IPAD: u8 = 0x36;
+const OPAD: u8 = 0x5c;
+
+#[derive(Clone)]
+pub struct HmacSha256 {
+    inner_start: Sha256<'static>,
+    outer_start: Sha256<'static>,
+    inner: Sha256<'static>,
+    outer: Sha256<'static>,
+}
+
+impl HmacSha256 {
+    pub fn new(key: &[u8]) -> HmacSha256 {
+        let mut block = [0; BLOCK_SIZE];
+        if key.len() > BLOCK_SIZE {
+            block[..32].copy_from_slice(&Sha256::digest(key));
+        } else {
+            block[..key.len()].copy_from_slice(key);
+        }
+
+        let mut inner_start = Sha256::new();
+        inner_start.update(&block.map(|k| k ^ IPAD)).unwrap();
+        let mut outer_start = Sha256::new();
+        outer_start.update(&block.map(|k| k ^ OPAD)).unwrap();
+
+        HmacSha256 {
+            inner: inner_start.clone(),
+            outer: outer_start.clone(),
+            inner_start,
+            outer_start,
+        }
+    }
+
+    pub fn mac(key: &[u8], message: &[u8]) -> [u8; 32] {
+        Self::new(key).update(message).finalize()
+    }
+
+    pub fn update(&mut self, message: &[u8]) -> &mut Self {
+        self.inner
+            .update(message)
+            .expect("message exceeds the SHA-256 length limit");
+        self
+    }
+
+    pub fn finalize(&mut self) -> [u8; 32] {
+        let inner = self.inner.finalize_reset();
+        self.inner.clone_from(&self.inner_start);
+
+        let res = self.outer.update(&inner).unwrap().finalize_reset();
+        self.outer.clone_from(&self.outer_start);
+        res
+    }
+
+    pub fn verify(&mut self, tag: &[u8]) -> bool {
+        ct::eq(&self.finalize(), tag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case_1() {
+        assert_eq!(
+            HmacSha256::mac(&[0x0b; 20], b"Hi There"),
+            [
+                0xb0, 0x34, 0x4c, 0x61, 0xd8, 0xdb, 0x38, 0x53, //
+                0x5c, 0xa8, 0xaf, 0xce, 0xaf, 0x0b, 0xf1, 0x2b, //
+                0x88, 0x1d, 0xc2, 0x00, 0xc9, 0x83, 0x3d, 0xa7, //
+                0x26, 0xe9, 0x37, 0x6c, 0x2e, 0x32, 0xcf, 0xf7, //
+            ]
+        );
+    }
+
+    #[test]
+    fn test_case_2() {
+        assert_eq!(
+            HmacSha256::mac(b"Jefe", b"what do ya want for nothing?"),
+            [
+                0x5b, 0xdc, 0xc1, 0x46, 0xbf, 0x60, 0x75, 0x4e, //
+                0x6a, 0x04, 0x24, 0x26, 0x08, 0x95, 0x75, 0xc7, //
+                0x5a, 0x00, 0x3f, 0x08, 0x9d, 0x27, 0x39, 0x83, //
+                0x9d, 0xec, 0x58, 0xb9, 0x64, 0xec, 0x38, 0x43, //
+            ]
+        );
+    }
+
+    #[test]
+    fn test_case_3() {
+        assert_eq!(
+            HmacSha256::mac(&[0xaa; 20], &[0xdd; 50]),
+            [
+                0x77, 0x3e, 0xa9, 0x1e, 0x36, 0x80, 0x0e, 0x46, //
+                0x85, 0x4d, 0xb8, 0xeb, 0xd0, 0x91, 0x81, 0xa7, //
+                0x29, 0x59, 0x09, 0x8b, 0x3e, 0xf8, 0xc1, 0x22, //
+                0xd9, 0x63, 0x55, 0x14, 0xce, 0xd5, 0x65, 0xfe, //
+            ]
+        );
+    }
+
+    #[test]
+    fn test_case_4() {
+        let key = [
+            0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, //
+            0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 0x10, //
+            0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, //
+            0x19, //
+        ];
+        assert_eq!(
+            HmacSha256::mac(&key, &[0xcd; 50]),
+            [
+                0x82, 0x55, 0x8a, 0x38, 0x9a, 0x44, 0x3c, 0x0e, //
+                0xa4, 0xcc, 0x81, 0x98, 0x99, 0xf2, 0x08, 0x3a, //
+                0x85, 0xf0, 0xfa, 0xa3, 0xe5, 0x78, 0xf8, 0x07, //
+                0x7a, 0x2e, 0x3f, 0xf4, 0x67, 0x29, 0x66, 0x5b, //
+            ]
+        );
+    }
+
+    #[test]
+    fn test_case_6() {
+        assert_eq!(
+            HmacSha256::mac(
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            ),
+            [
+                0x60, 0xe4, 0x31, 0x59, 0x1e, 0xe0, 0xb6, 0x7f, //
+                0x0d, 0x8a, 0x26, 0xaa, 0xcb, 0xf5, 0xb7, 0x7f, //
+                0x8e, 0x0b, 0xc6, 0x21, 0x37, 0x28, 0xc5, 0x14, //
+                0x05, 0x46, 0x04, 0x0f, 0x0e, 0xe3, 0x7f, 0x54, //
+            ]
+        );
+    }
+
+    #[test]
+    fn test_case_7() {
+        let message: &[u8] = b"This is a test using a larger than block-size key and a larger \
+            than block-size data. The key needs to be hashed before being used by the HMAC \
+            algorithm.";
+        assert_eq!(
+            HmacSha256::mac(&[0xaa; 131], message),
+            [
+                0x9b, 0x09, 0xff, 0xa7, 0x1b, 0x94, 0x2f, 0xcb, //
+                0x27, 0x63, 0x5f, 0xbc, 0xd5, 0xb0, 0xe9, 0x44, //
+                0xbf, 0xdc, 0x63, 0x64, 0x4f, 0x07, 0x13, 0x93, //
+                0x8a, 0x7f, 0x51, 0x53, 0x5c, 0x3a, 0x35, 0xe2, //
+            ]
+        );
+    }
+
+    #[test]
+    fn test_incremental() {
+        let mut hmac = HmacSha256::new(b"Jefe");
+        let tag = hmac
+            .update(b"what do ya ")
+            .update(b"want for nothing?")
+            .finalize();
+        assert_eq!(
+            HmacSha256::mac(b"Jefe", b"what do ya want for nothing?"),
+            tag
+        );
+        assert_eq!(tag, hmac.update(b"what do ya want for nothing?").finalize());
+    }
+
+    #[test]
+    fn test_verify() {
+        let tag = HmacSha256::mac(&[0x0c; 20], b"Test With Truncation");
+        assert!(HmacSha256::new(&[0x0c; 20])
+            .update(b"Test With Truncation")
+            .verify(&tag));
+        assert!(!HmacSha256::new(&[0x0c; 20])
+            .update(b"Test With Truncation")
+            .verify(&tag[..16]));
+        assert!(!HmacSha256::new(&[0x0d; 20])
+            .update(b"Test With Truncation")
+            .verify(&tag));
+    }
+}
@@ -1,5 +1,19 @@
+pub mod cbc;
+pub mod cmac;
+pub mod ct;
+pub mod ctr;
+pub mod entropy;
+pub mod fortuna;
 pub mod galois;
+pub mod gcm;
+pub mod gcm_siv;
+pub mod hkdf;
+pub mod hmac;
 pub mod mod255;
+pub mod pbkdf2;
 pub mod prime;
 pub mod rijndael;
 pub mod sha;
+
+#[cfg(test)]
+mod test_vectors;
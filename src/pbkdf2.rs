@@ -0,0 +1,155 @@
+use crate::hmac::HmacSha256;
+use std::error;
+use std::fmt;
+
+const HASH_SIZE: usize = 32;
+const MAX_OUTPUT_SIZE: u64 = u32::MAX as u64 * HASH_SIZE as u64;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    ZeroIterations,
+    OutputTooLong,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::ZeroIterations => write!(f, "iteration count must be positive"),
+            Error::OutputTooLong => write!(f, "derived key too long"),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+pub fn pbkdf2_sha256(
+    password: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    out: &mut [u8],
+) -> Result<(), Error> {
+    if iterations == 0 {
+        return Err(Error::ZeroIterations);
+    }
+    if out.len() as u64 > MAX_OUTPUT_SIZE {
+        return Err(Error::OutputTooLong);
+    }
+
+    let mut prf = HmacSha256::new(password);
+    for (i, chunk) in out.chunks_mut(HASH_SIZE).enumerate() {
+        let mut u = prf
+            .update(salt)
+            .update(&(i as u32 + 1).to_be_bytes())
+            .finalize();
+        let mut t = u;
+
+        for _ in 1..iterations {
+            u = prf.update(&u).finalize();
+            for (t, &u) in t.iter_mut().zip(&u) {
+                *t ^= u;
+            }
+        }
+
+        chunk.copy_from_slice(&t[..chunk.len()]);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_one_iteration() {
+        let mut out = [0; 32];
+        pbkdf2_sha256(b"password", b"salt", 1, &mut out).unwrap();
+        assert_eq!(
+            out[..],
+            [
+                0x12, 0x0f, 0xb6, 0xcf, 0xfc, 0xf8, 0xb3, 0x2c, //
+                0x43, 0xe7, 0x22, 0x52, 0x56, 0xc4, 0xf8, 0x37, //
+                0xa8, 0x65, 0x48, 0xc9, 0x2c, 0xcc, 0x35, 0x48, //
+                0x08, 0x05, 0x98, 0x7c, 0xb7, 0x0b, 0xe1, 0x7b, //
+            ][..]
+        );
+    }
+
+    #[test]
+    fn test_two_iterations() {
+        let mut out = [0; 32];
+        pbkdf2_sha256(b"password", b"salt", 2, &mut out).unwrap();
+        assert_eq!(
+            out[..],
+            [
+                0xae, 0x4d, 0x0c, 0x95, 0xaf, 0x6b, 0x46, 0xd3, //
+                0x2d, 0x0a, 0xdf, 0xf9, 0x28, 0xf0, 0x6d, 0xd0, //
+                0x2a, 0x30, 0x3f, 0x8e, 0xf3, 0xc2, 0x51, 0xdf, //
+                0xd6, 0xe2, 0xd8, 0x5a, 0x95, 0x47, 0x4c, 0x43, //
+            ][..]
+        );
+    }
+
+    #[test]
+    fn test_4096_iterations() {
+        let mut out = [0; 32];
+        pbkdf2_sha256(b"password", b"salt", 4096, &mut out).unwrap();
+        assert_eq!(
+            out[..],
+            [
+                0xc5, 0xe4, 0x78, 0xd5, 0x92, 0x88, 0xc8, 0x41, //
+                0xaa, 0x53, 0x0d, 0xb6, 0x84, 0x5c, 0x4c, 0x8d, //
+                0x96, 0x28, 0x93, 0xa0, 0x01, 0xce, 0x4e, 0x11, //
+                0xa4, 0x96, 0x38, 0x73, 0xaa, 0x98, 0x13, 0x4a, //
+            ][..]
+        );
+    }
+
+    #[test]
+    fn test_long_output() {
+        let mut out = [0; 40];
+        pbkdf2_sha256(
+            b"passwordPASSWORDpassword",
+            b"saltSALTsaltSALTsaltSALTsaltSALTsalt",
+            4096,
+            &mut out,
+        )
+        .unwrap();
+        assert_eq!(
+            out[..],
+            [
+                0x34, 0x8c, 0x89, 0xdb, 0xcb, 0xd3, 0x2b, 0x2f, //
+                0x32, 0xd8, 0x14, 0xb8, 0x11, 0x6e, 0x84, 0xcf, //
+                0x2b, 0x17, 0x34, 0x7e, 0xbc, 0x18, 0x00, 0x18, //
+                0x1c, 0x4e, 0x2a, 0x1f, 0xb8, 0xdd, 0x53, 0xe1, //
+                0xc6, 0x35, 0x51, 0x8c, 0x7d, 0xac, 0x47, 0xe9, //
+            ][..]
+        );
+    }
+
+    #[test]
+    fn test_rfc_7914() {
+        let mut out = [0; 64];
+        pbkdf2_sha256(b"passwd", b"salt", 1, &mut out).unwrap();
+        assert_eq!(
+            out[..],
+            [
+                0x55, 0xac, 0x04, 0x6e, 0x56, 0xe3, 0x08, 0x9f, //
+                0xec, 0x16, 0x91, 0xc2, 0x25, 0x44, 0xb6, 0x05, //
+                0xf9, 0x41, 0x85, 0x21, 0x6d, 0xde, 0x04, 0x65, //
+                0xe6, 0x8b, 0x9d, 0x57, 0xc2, 0x0d, 0xac, 0xbc, //
+                0x49, 0xca, 0x9c, 0xcc, 0xf1, 0x79, 0xb6, 0x45, //
+                0x99, 0x16, 0x64, 0xb3, 0x9d, 0x77, 0xef, 0x31, //
+                0x7c, 0x71, 0xb8, 0x45, 0xb1, 0xe3, 0x0b, 0xd5, //
+                0x09, 0x11, 0x20, 0x41, 0xd3, 0xa1, 0x97, 0x83, //
+            ][..]
+        );
+    }
+
+    #[test]
+    fn test_zero_iterations() {
+        assert_eq!(
+            Err(Error::ZeroIterations),
+            pbkdf2_sha256(b"password", b"salt", 0, &mut [0; 32])
+        );
+    }
+}
@@ -4,18 +4,140 @@ mod key_expansion;
 mod mix_columns;
 mod sbox;
 
+pub const BLOCK_SIZE: usize = 16;
+
 #[derive(Debug, PartialEq)]
-pub struct Aes256 {
+pub struct State {
     state: [Gf256; 16],
 }
 
-fn add_round_key(Aes256 { state }: &mut Aes256, key: &[Gf256; 240], i: usize) {
+pub trait BlockCipher {
+    type Key;
+    type Schedule: AsRef<[Gf256]>;
+
+    fn expand_key(key: &Self::Key) -> Self::Schedule;
+
+    fn encrypt_block(state: &mut State, key: &Self::Schedule) {
+        encrypt_block(state, key.as_ref());
+    }
+
+    fn decrypt_block(state: &mut State, key: &Self::Schedule) {
+        decrypt_block(state, key.as_ref());
+    }
+}
+
+#[derive(Debug)]
+pub struct Aes128;
+
+#[derive(Debug)]
+pub struct Aes192;
+
+#[derive(Debug)]
+pub struct Aes256;
+
+pub struct Key<C: BlockCipher> {
+    schedule: C::Schedule,
+}
+
+pub type Aes128Key = Key<Aes128>;
+pub type Aes192Key = Key<Aes192>;
+pub type Aes256Key = Key<Aes256>;
+
+impl From<[u8; 16]> for State {
+    fn from(block: [u8; 16]) -> Self {
+        State {
+            state: block.map(Gf256),
+        }
+    }
+}
+
+impl From<State> for [u8; 16] {
+    fn from(State { state }: State) -> Self {
+        state.map(u8::from)
+    }
+}
+
+impl<C: BlockCipher> Key<C> {
+    pub fn new(key: &C::Key) -> Self {
+        Key {
+            schedule: C::expand_key(key),
+        }
+    }
+
+    pub fn encrypt_block(&self, block: &mut [u8; 16]) {
+        let mut state = State::from(*block);
+        C::encrypt_block(&mut state, &self.schedule);
+        *block = state.into();
+    }
+
+    pub fn decrypt_block(&self, block: &mut [u8; 16]) {
+        let mut state = State::from(*block);
+        C::decrypt_block(&mut state, &self.schedule);
+        *block = state.into();
+    }
+}
+
+impl<C: BlockCipher> Clone for Key<C>
+where
+    C::Schedule: Clone,
+{
+    fn clone(&self) -> Self {
+        Key {
+            schedule: self.schedule.clone(),
+        }
+    }
+}
+
+fn expand<S: AsMut<[Gf256]>>(key: &[u8], mut schedule: S) -> S {
+    for (&from, to) in key.iter().zip(schedule.as_mut().iter_mut()) {
+        *to = Gf256(from);
+    }
+    key_expansion::expand_key(schedule.as_mut());
+    schedule
+}
+
+impl BlockCipher for Aes128 {
+    type Key = [u8; 16];
+    type Schedule = [Gf256; 176];
+
+    fn expand_key(key: &[u8; 16]) -> [Gf256; 176] {
+        expand(key, [Gf256(0); 176])
+    }
+}
+
+impl BlockCipher for Aes192 {
+    type Key = [u8; 24];
+    type Schedule = [Gf256; 208];
+
+    fn expand_key(key: &[u8; 24]) -> [Gf256; 208] {
+        expand(key, [Gf256(0); 208])
+    }
+}
+
+impl BlockCipher for Aes256 {
+    type Key = [u8; 32];
+    type Schedule = [Gf256; 240];
+
+    fn expand_key(key: &[u8; 32]) -> [Gf256; 240] {
+        expand(key, [Gf256(0); 240])
+    }
+}
+
+fn rounds(key: &[Gf256]) -> usize {
+    assert!(
+        [176, 208, 240].contains(&key.len()),
+        "invalid key schedule length"
+    );
+    key.len() / 16 - 1
+}
+
+fn add_round_key(State { state }: &mut State, key: &[Gf256], i: usize) {
     for (s, &k) in state.iter_mut().zip(key[i * 16..(i + 1) * 16].iter()) {
         *s += k;
     }
 }
 
-fn sub_bytes(Aes256 { state }: &mut Aes256) {
+fn sub_bytes(State { state }: &mut State) {
     let sbox = &*sbox::SBOX;
 
     for item in state.iter_mut() {
@@ -23,7 +145,15 @@ fn sub_bytes(Aes256 { state }: &mut Aes256) {
     }
 }
 
-fn shift_rows(Aes256 { state }: &mut Aes256) {
+fn inv_sub_bytes(State { state }: &mut State) {
+    let sbox = &*sbox::SBOX;
+
+    for item in state.iter_mut() {
+        *item = sbox.inverse(*item);
+    }
+}
+
+fn shift_rows(State { state }: &mut State) {
     let mut t = state[1];
 
     state[1] = state[5];
@@ -45,17 +175,54 @@ fn shift_rows(Aes256 { state }: &mut Aes256) {
     state[7] = t;
 }
 
-fn mix_columns(Aes256 { state }: &mut Aes256) {
+fn inv_shift_rows(State { state }: &mut State) {
+    let mut t = state[13];
+
+    state[13] = state[9];
+    state[9] = state[5];
+    state[5] = state[1];
+    state[1] = t;
+
+    t = state[2];
+    state[2] = state[10];
+    state[10] = t;
+    t = state[6];
+    state[6] = state[14];
+    state[14] = t;
+
+    t = state[7];
+    state[7] = state[11];
+    state[11] = state[15];
+    state[15] = state[3];
+    state[3] = t;
+}
+
+fn mix_columns(State { state }: &mut State) {
     mix_columns::mix_column(&mut state[0..4]);
     mix_columns::mix_column(&mut state[4..8]);
     mix_columns::mix_column(&mut state[8..12]);
     mix_columns::mix_column(&mut state[12..16]);
 }
 
-pub fn encrypt_block(state: &mut Aes256, key: &[Gf256; 240]) {
+fn inv_mix_columns(State { state }: &mut State) {
+    mix_columns::inv_mix_column(&mut state[0..4]);
+    mix_columns::inv_mix_column(&mut state[4..8]);
+    mix_columns::inv_mix_column(&mut state[8..12]);
+    mix_columns::inv_mix_column(&mut state[12..16]);
+}
+
+pub fn xor_block(block: &mut [u8; BLOCK_SIZE], other: &[u8]) {
+    for (b, &o) in block.iter_mut().zip(other.iter()) {
+        *b ^= o;
+    }
+}
+
+pub fn encrypt_block(state: &mut State, key: &[Gf256]) {
+    let rounds = rounds(key);
+
     add_round_key(state, key, 0);
 
-    for i in 1..14 {
+    for i in 1..rounds {
         sub_bytes(state);
         shift_rows(state);
         mix_columns(state);
@@ -64,36 +231,78 @@ pub fn encrypt_block(state: &mut Aes256, key: &[Gf256; 240]) {
 
     sub_bytes(state);
     shift_rows(state);
-    add_round_key(state, key, 14);
+    add_round_key(state, key, rounds);
+}
+
+pub fn decrypt_block(state: &mut State, key: &[Gf256]) {
+    let rounds = rounds(key);
+
+    add_round_key(state, key, rounds);
+    inv_shift_rows(state);
+    inv_sub_bytes(state);
+
+    for i in (1..rounds).rev() {
+        add_round_key(state, key, i);
+        inv_mix_columns(state);
+        inv_shift_rows(state);
+        inv_sub_bytes(state);
+    }
+
+    add_round_key(state, key, 0);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use arraymap::ArrayMap;
 
     #[test]
     fn test_shift_rows() {
-        let mut s = Aes256 {
+        let mut s = State {
             state: [
                 0x00, 0x10, 0x20, 0x30, //
                 0x01, 0x11, 0x21, 0x31, //
                 0x02, 0x12, 0x22, 0x32, //
                 0x03, 0x13, 0x23, 0x33, //
             ]
-            .map(|&v| Gf256(v)),
+            .map(Gf256),
         };
         shift_rows(&mut s);
         assert_eq!(
             s,
-            Aes256 {
+            State {
                 state: [
                     0x00, 0x11, 0x22, 0x33, //
                     0x01, 0x12, 0x23, 0x30, //
                     0x02, 0x13, 0x20, 0x31, //
                     0x03, 0x10, 0x21, 0x32, //
                 ]
-                .map(|&v| Gf256(v))
+                .map(Gf256)
+            }
+        );
+    }
+
+    #[test]
+    fn test_inv_shift_rows() {
+        let mut s = State {
+            state: [
+                0x00, 0x11, 0x22, 0x33, //
+                0x01, 0x12, 0x23, 0x30, //
+                0x02, 0x13, 0x20, 0x31, //
+                0x03, 0x10, 0x21, 0x32, //
+            ]
+            .map(Gf256),
+        };
+        inv_shift_rows(&mut s);
+        assert_eq!(
+            s,
+            State {
+                state: [
+                    0x00, 0x10, 0x20, 0x30, //
+                    0x01, 0x11, 0x21, 0x31, //
+                    0x02, 0x12, 0x22, 0x32, //
+                    0x03, 0x13, 0x23, 0x33, //
+                ]
+                .map(Gf256)
             }
         );
     }
@@ -106,29 +315,151 @@ mod tests {
             0x1f, 0x35, 0x2c, 0x07, 0x3b, 0x61, 0x08, 0xd7, //
             0x2d, 0x98, 0x10, 0xa3, 0x09, 0x14, 0xdf, 0xf4, //
         ];
-        let mut expanded_key = [Gf256(0); 240];
-
-        for (&from, to) in key.iter().zip(expanded_key.iter_mut()) {
-            *to = Gf256(from);
-        }
-        key_expansion::expand_key(&mut expanded_key);
-        let mut block = Aes256 {
+        let expanded_key = Aes256::expand_key(&key);
+        let mut block = State {
             state: [
                 0xae, 0x2d, 0x8a, 0x57, 0x1e, 0x03, 0xac, 0x9c, //
                 0x9e, 0xb7, 0x6f, 0xac, 0x45, 0xaf, 0x8e, 0x51, //
             ]
-            .map(|&v| Gf256(v)),
+            .map(Gf256),
         };
-        encrypt_block(&mut block, &expanded_key);
+        Aes256::encrypt_block(&mut block, &expanded_key);
         assert_eq!(
             block,
-            Aes256 {
+            State {
                 state: [
                     0x59, 0x1c, 0xcb, 0x10, 0xd4, 0x10, 0xed, 0x26, //
                     0xdc, 0x5b, 0xa7, 0x4a, 0x31, 0x36, 0x28, 0x70, //
                 ]
-                .map(|&v| Gf256(v))
+                .map(Gf256)
+            }
+        );
+    }
+
+    #[test]
+    fn test_decrypt_block() {
+        let key = [
+            0x60, 0x3d, 0xeb, 0x10, 0x15, 0xca, 0x71, 0xbe, //
+            0x2b, 0x73, 0xae, 0xf0, 0x85, 0x7d, 0x77, 0x81, //
+            0x1f, 0x35, 0x2c, 0x07, 0x3b, 0x61, 0x08, 0xd7, //
+            0x2d, 0x98, 0x10, 0xa3, 0x09, 0x14, 0xdf, 0xf4, //
+        ];
+        let expanded_key = Aes256::expand_key(&key);
+        let mut block = State {
+            state: [
+                0x59, 0x1c, 0xcb, 0x10, 0xd4, 0x10, 0xed, 0x26, //
+                0xdc, 0x5b, 0xa7, 0x4a, 0x31, 0x36, 0x28, 0x70, //
+            ]
+            .map(Gf256),
+        };
+        Aes256::decrypt_block(&mut block, &expanded_key);
+        assert_eq!(
+            block,
+            State {
+                state: [
+                    0xae, 0x2d, 0x8a, 0x57, 0x1e, 0x03, 0xac, 0x9c, //
+                    0x9e, 0xb7, 0x6f, 0xac, 0x45, 0xaf, 0x8e, 0x51, //
+                ]
+                .map(Gf256)
+            }
+        );
+    }
+
+    fn assert_round_trip<C: BlockCipher>(key: &C::Key, ciphertext: [u8; 16]) {
+        let expanded_key = C::expand_key(key);
+        let plaintext = [
+            0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, //
+            0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff, //
+        ]
+        .map(Gf256);
+        let mut block = State { state: plaintext };
+
+        C::encrypt_block(&mut block, &expanded_key);
+        assert_eq!(
+            block,
+            State {
+                state: ciphertext.map(Gf256)
             }
         );
+
+        C::decrypt_block(&mut block, &expanded_key);
+        assert_eq!(block, State { state: plaintext });
+    }
+
+    #[test]
+    fn test_fips_197_aes_128() {
+        assert_round_trip::<Aes128>(
+            &[
+                0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, //
+                0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, //
+            ],
+            [
+                0x69, 0xc4, 0xe0, 0xd8, 0x6a, 0x7b, 0x04, 0x30, //
+                0xd8, 0xcd, 0xb7, 0x80, 0x70, 0xb4, 0xc5, 0x5a, //
+            ],
+        );
+    }
+
+    #[test]
+    fn test_fips_197_aes_192() {
+        assert_round_trip::<Aes192>(
+            &[
+                0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, //
+                0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, //
+                0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, //
+            ],
+            [
+                0xdd, 0xa9, 0x7c, 0xa4, 0x86, 0x4c, 0xdf, 0xe0, //
+                0x6e, 0xaf, 0x70, 0xa0, 0xec, 0x0d, 0x71, 0x91, //
+            ],
+        );
+    }
+
+    #[test]
+    fn test_fips_197_aes_256() {
+        assert_round_trip::<Aes256>(
+            &[
+                0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, //
+                0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, //
+                0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, //
+                0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f, //
+            ],
+            [
+                0x8e, 0xa2, 0xb7, 0xca, 0x51, 0x67, 0x45, 0xbf, //
+                0xea, 0xfc, 0x49, 0x90, 0x4b, 0x49, 0x60, 0x89, //
+            ],
+        );
+    }
+
+    #[test]
+    fn test_key() {
+        let key = Aes256Key::new(&[
+            0x60, 0x3d, 0xeb, 0x10, 0x15, 0xca, 0x71, 0xbe, //
+            0x2b, 0x73, 0xae, 0xf0, 0x85, 0x7d, 0x77, 0x81, //
+            0x1f, 0x35, 0x2c, 0x07, 0x3b, 0x61, 0x08, 0xd7, //
+            0x2d, 0x98, 0x10, 0xa3, 0x09, 0x14, 0xdf, 0xf4, //
+        ]);
+        let mut block = [
+            0xae, 0x2d, 0x8a, 0x57, 0x1e, 0x03, 0xac, 0x9c, //
+            0x9e, 0xb7, 0x6f, 0xac, 0x45, 0xaf, 0x8e, 0x51, //
+        ];
+
+        key.encrypt_block(&mut block);
+        assert_eq!(
+            block,
+            [
+                0x59, 0x1c, 0xcb, 0x10, 0xd4, 0x10, 0xed, 0x26, //
+                0xdc, 0x5b, 0xa7, 0x4a, 0x31, 0x36, 0x28, 0x70, //
+            ]
+        );
+
+        key.decrypt_block(&mut block);
+        assert_eq!(
+            block,
+            [
+                0xae, 0x2d, 0x8a, 0x57, 0x1e, 0x03, 0xac, 0x9c, //
+                0x9e, 0xb7, 0x6f, 0xac, 0x45, 0xaf, 0x8e, 0x51, //
+            ]
+        );
     }
 }
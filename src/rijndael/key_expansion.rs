@@ -33,27 +33,26 @@ fn schedule_core(r: &mut [Gf256; 4], i: usize) {
     r[0] += round_constant(i);
 }
 
-#[allow(dead_code)]
-pub fn expand_key(key: &mut [Gf256; 240]) {
+pub fn expand_key(key: &mut [Gf256]) {
+    assert!(
+        [176, 208, 240].contains(&key.len()),
+        "invalid key schedule length"
+    );
+    let rounds = key.len() / 16 - 1;
+    let n = 4 * (rounds - 6);
     let mut t: [Gf256; 4] = Default::default();
-    let mut i = 1;
 
-    for c in (32..240).step_by(4) {
+    for c in (n..key.len()).step_by(4) {
         t.copy_from_slice(&key[c - 4..c]);
 
-        match c & 0x1f {
-            0 => {
-                schedule_core(&mut t, i);
-                i += 1;
-            }
-            16 => {
-                apply_sbox(&mut t);
-            }
-            _ => {}
+        if c % n == 0 {
+            schedule_core(&mut t, c / n);
+        } else if n > 24 && c % n == 16 {
+            apply_sbox(&mut t);
         }
 
         for (a, &item) in t.iter().enumerate() {
-            key[c + a] = key[c + a - 32] + item;
+            key[c + a] = key[c + a - n] + item;
         }
     }
 }
@@ -61,13 +60,12 @@ pub fn expand_key(key: &mut [Gf256; 240]) {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use arraymap::ArrayMap;
 
     #[test]
     fn test_rotate() {
-        let mut r = [0x1d, 0x2c, 0x3a, 0x4f].map(|&v| Gf256(v));
+        let mut r = [0x1d, 0x2c, 0x3a, 0x4f].map(Gf256);
         rotate(&mut r);
-        assert_eq!(r, [0x2c, 0x3a, 0x4f, 0x1d].map(|&v| Gf256(v)));
+        assert_eq!(r, [0x2c, 0x3a, 0x4f, 0x1d].map(Gf256));
     }
 
     #[test]
@@ -88,11 +86,60 @@ mod tests {
     fn test_schedule_core() {
         let mut r = [Gf256(0); 4];
         schedule_core(&mut r, 1);
-        assert_eq!(r, [0x62, 0x63, 0x63, 0x63].map(|&v| Gf256(v)));
+        assert_eq!(r, [0x62, 0x63, 0x63, 0x63].map(Gf256));
 
-        r = [0, 1, 2, 3].map(|&v| Gf256(v));
+        r = [0, 1, 2, 3].map(Gf256);
         schedule_core(&mut r, 2);
-        assert_eq!(r, [0x7e, 0x77, 0x7b, 0x63].map(|&v| Gf256(v)));
+        assert_eq!(r, [0x7e, 0x77, 0x7b, 0x63].map(Gf256));
+    }
+
+    #[test]
+    fn test_expand_key_128() {
+        let mut key = [Gf256(0); 176];
+        key[..16].copy_from_slice(
+            &[
+                0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, //
+                0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c, //
+            ]
+            .map(Gf256),
+        );
+        expand_key(&mut key);
+        assert_eq!(
+            key[16..32].iter().map(|&Gf256(v)| v).collect::<Vec<u8>>(),
+            vec![
+                0xa0, 0xfa, 0xfe, 0x17, 0x88, 0x54, 0x2c, 0xb1, //
+                0x23, 0xa3, 0x39, 0x39, 0x2a, 0x6c, 0x76, 0x05, //
+            ]
+        );
+        assert_eq!(
+            key[160..].iter().map(|&Gf256(v)| v).collect::<Vec<u8>>(),
+            vec![
+                0xd0, 0x14, 0xf9, 0xa8, 0xc9, 0xee, 0x25, 0x89, //
+                0xe1, 0x3f, 0x0c, 0xc8, 0xb6, 0x63, 0x0c, 0xa6, //
+            ]
+        );
+    }
+
+    #[test]
+    fn test_expand_key_192() {
+        let mut key = [Gf256(0); 208];
+        key[..24].copy_from_slice(
+            &[
+                0x8e, 0x73, 0xb0, 0xf7, 0xda, 0x0e, 0x64, 0x52, //
+                0xc8, 0x10, 0xf3, 0x2b, 0x80, 0x90, 0x79, 0xe5, //
+                0x62, 0xf8, 0xea, 0xd2, 0x52, 0x2c, 0x6b, 0x7b, //
+            ]
+            .map(Gf256),
+        );
+        expand_key(&mut key);
+        assert_eq!(
+            key[24..32].iter().map(|&Gf256(v)| v).collect::<Vec<u8>>(),
+            vec![0xfe, 0x0c, 0x91, 0xf7, 0x24, 0x02, 0xf5, 0xa5]
+        );
+        assert_eq!(
+            key[200..].iter().map(|&Gf256(v)| v).collect::<Vec<u8>>(),
+            vec![0x8e, 0xcc, 0x72, 0x04, 0x01, 0x00, 0x22, 0x02]
+        );
     }
 
     #[test]
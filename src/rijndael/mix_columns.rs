@@ -16,8 +16,7 @@ pub fn mix_column(r: &mut [Gf256]) {
     r[3] = b[3] + a[2] + a[1] + b[0] + a[0];
 }
 
-#[allow(dead_code)]
-pub fn inv_mix_column(r: &mut [Gf256; 4]) {
+pub fn inv_mix_column(r: &mut [Gf256]) {
     let mut a: [Gf256; 4] = Default::default();
     a.copy_from_slice(r);
 
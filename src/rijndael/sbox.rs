@@ -45,7 +45,6 @@ impl Sbox {
         self.direct[n as usize]
     }
 
-    #[allow(dead_code)]
     pub fn inverse(&self, Gf256(n): Gf256) -> Gf256 {
         self.inverse[n as usize]
     }
@@ -59,5 +58,7 @@ mod tests {
     fn test_sbox() {
         assert_eq!(Gf256(0x63), SBOX.direct(Gf256(0x00)));
         assert_eq!(Gf256(0xda), SBOX.direct(Gf256(0x7a)));
+        assert_eq!(Gf256(0x00), SBOX.inverse(Gf256(0x63)));
+        assert_eq!(Gf256(0x7a), SBOX.inverse(Gf256(0xda)));
     }
 }
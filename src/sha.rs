@@ -1,9 +1,33 @@
-use byteorder::{BigEndian, WriteBytesExt};
+use byteorder::{BigEndian, ByteOrder};
 use crate::prime;
 use lazy_static::lazy_static;
+use std::error;
+use std::fmt;
 
 mod block_expansion;
+#[cfg(feature = "digest")]
+mod digest;
+mod io;
 mod padding;
+mod sha512;
+mod state;
+
+pub use self::io::HashingReader;
+pub use self::sha512::{Sha384, Sha512, Sha512_224, Sha512_256};
+pub use self::state::{InvalidState, Sha256State};
+
+const MAX_LENGTH: u64 = u64::MAX / 8;
+
+#[derive(Debug, PartialEq)]
+pub struct LengthOverflow;
+
+impl fmt::Display for LengthOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "message exceeds the SHA-256 length limit")
+    }
+}
+
+impl error::Error for LengthOverflow {}
 
 fn ch(x: u32, y: u32, z: u32) -> u32 {
     (x & y) ^ (!x & z)
@@ -52,14 +76,35 @@ lazy_static! {
             init_hash,
         }
     };
+    static ref SHA224_TABLES: Tables = {
+        let mut primes = prime::Primes::new();
+        Tables {
+            round_constants: TABLES.round_constants.clone(),
+            init_hash: primes
+                .into_iter()
+                .skip(8)
+                .take(8)
+                .map(sha512::to_root_fpart64(2))
+                .map(|h| h as u32)
+                .collect(),
+        }
+    };
 }
 
+#[derive(Clone)]
 pub struct Sha256<'a> {
-    message: Vec<u8>,
-    length: usize,
+    message: [u8; 64],
+    message_len: usize,
+    length: u64,
     hash: Hasher<'a>,
 }
 
+#[derive(Clone)]
+pub struct Sha224<'a> {
+    sha: Sha256<'a>,
+}
+
+#[derive(Clone)]
 pub struct Hasher<'a> {
     hash: [u32; 8],
     tables: &'a Tables,
@@ -68,9 +113,13 @@ pub struct Hasher<'a> {
 
 impl Sha256<'_> {
     pub fn new<'a>() -> Sha256<'a> {
-        let tables = &*TABLES;
+        Self::with_tables(&TABLES)
+    }
+
+    fn with_tables(tables: &Tables) -> Sha256<'_> {
         let mut res = Sha256 {
-            message: Vec::with_capacity(128),
+            message: [0; 64],
+            message_len: 0,
             length: 0,
             hash: Hasher {
                 tables,
@@ -83,38 +132,107 @@ impl Sha256<'_> {
     }
 
     pub fn hash<'a, T: IntoIterator<Item = &'a u8>>(message: T) -> Vec<u8> {
-        Self::new().extend(message).flush()
+        Self::digest(message).to_vec()
+    }
+
+    pub fn digest<'a, T: IntoIterator<Item = &'a u8>>(message: T) -> [u8; 32] {
+        Self::new().extend(message).finalize_reset()
     }
 
     pub fn extend<'a, T: IntoIterator<Item = &'a u8>>(&mut self, message: T) -> &mut Self {
+        self.try_extend(message)
+            .expect("message exceeds the SHA-256 length limit")
+    }
+
+    pub fn try_extend<'a, T: IntoIterator<Item = &'a u8>>(
+        &mut self,
+        message: T,
+    ) -> Result<&mut Self, LengthOverflow> {
         let mut iter = message.into_iter();
         loop {
-            let l = self.message.len();
-            self.message.extend(iter.by_ref().take(64 - l));
-            self.length += self.message.len() - l;
-            if self.message.len() < 64 {
-                return self;
+            let l = self.message_len;
+            let mut n = l;
+            for (b, &m) in self.message[l..].iter_mut().zip(iter.by_ref()) {
+                *b = m;
+                n += 1;
+            }
+            self.add_length(n - l)?;
+            self.message_len = n;
+            if self.message_len < 64 {
+                return Ok(self);
+            }
+            self.hash.hash_block(&self.message);
+            self.message_len = 0;
+        }
+    }
+
+    fn add_length(&mut self, n: usize) -> Result<(), LengthOverflow> {
+        match self.length.checked_add(n as u64) {
+            Some(length) if length <= MAX_LENGTH => {
+                self.length = length;
+                Ok(())
+            }
+            _ => Err(LengthOverflow),
+        }
+    }
+
+    pub fn update(&mut self, mut message: &[u8]) -> Result<&mut Self, LengthOverflow> {
+        self.add_length(message.len())?;
+
+        if self.message_len > 0 {
+            let l = (64 - self.message_len).min(message.len());
+            self.message[self.message_len..self.message_len + l].copy_from_slice(&message[..l]);
+            self.message_len += l;
+            message = &message[l..];
+            if self.message_len < 64 {
+                return Ok(self);
             }
             self.hash.hash_block(&self.message);
-            self.message.clear();
+            self.message_len = 0;
+        }
+
+        let mut blocks = message.chunks_exact(64);
+        for block in &mut blocks {
+            self.hash.hash_block(block);
         }
+        let remainder = blocks.remainder();
+        self.message[..remainder.len()].copy_from_slice(remainder);
+        self.message_len = remainder.len();
+        Ok(self)
     }
 
     fn hash_message_remainder(&mut self) {
-        padding::pad_448(&mut self.message);
-        self.message
-            .write_u64::<BigEndian>((self.length * 8) as u64)
-            .unwrap();
-        for block in self.message.chunks(64) {
+        let mut blocks = [0; 128];
+        blocks[..self.message_len].copy_from_slice(&self.message[..self.message_len]);
+        let l = padding::pad_448(&mut blocks, self.message_len);
+        BigEndian::write_u64(&mut blocks[l..l + 8], self.length * 8);
+        for block in blocks[..l + 8].chunks(64) {
             self.hash.hash_block(block);
         }
     }
 
-    pub fn flush(&mut self) -> Vec<u8> {
+    pub fn finalize(mut self) -> [u8; 32] {
+        self.finalize_reset()
+    }
+
+    pub fn finalize_into(mut self, out: &mut [u8; 32]) {
+        *out = self.finalize_reset();
+    }
+
+    pub fn finalize_copy(&self) -> [u8; 32] {
+        self.clone().finalize()
+    }
+
+    pub fn finalize_reset(&mut self) -> [u8; 32] {
         self.hash_message_remainder();
-        self.message.clear();
+        self.message_len = 0;
+        self.length = 0;
         self.hash.reset()
     }
+
+    pub fn flush(&mut self) -> Vec<u8> {
+        self.finalize_reset().to_vec()
+    }
 }
 
 impl Default for Sha256<'_> {
@@ -123,6 +241,68 @@ impl Default for Sha256<'_> {
     }
 }
 
+impl Sha224<'_> {
+    pub fn new<'a>() -> Sha224<'a> {
+        Sha224 {
+            sha: Sha256::with_tables(&SHA224_TABLES),
+        }
+    }
+
+    pub fn hash<'a, T: IntoIterator<Item = &'a u8>>(message: T) -> Vec<u8> {
+        Self::digest(message).to_vec()
+    }
+
+    pub fn digest<'a, T: IntoIterator<Item = &'a u8>>(message: T) -> [u8; 28] {
+        Self::new().extend(message).finalize_reset()
+    }
+
+    pub fn extend<'a, T: IntoIterator<Item = &'a u8>>(&mut self, message: T) -> &mut Self {
+        self.sha.extend(message);
+        self
+    }
+
+    pub fn try_extend<'a, T: IntoIterator<Item = &'a u8>>(
+        &mut self,
+        message: T,
+    ) -> Result<&mut Self, LengthOverflow> {
+        self.sha.try_extend(message)?;
+        Ok(self)
+    }
+
+    pub fn update(&mut self, message: &[u8]) -> Result<&mut Self, LengthOverflow> {
+        self.sha.update(message)?;
+        Ok(self)
+    }
+
+    pub fn finalize(mut self) -> [u8; 28] {
+        self.finalize_reset()
+    }
+
+    pub fn finalize_into(mut self, out: &mut [u8; 28]) {
+        *out = self.finalize_reset();
+    }
+
+    pub fn finalize_copy(&self) -> [u8; 28] {
+        self.clone().finalize()
+    }
+
+    pub fn finalize_reset(&mut self) -> [u8; 28] {
+        let mut res = [0; 28];
+        res.copy_from_slice(&self.sha.finalize_reset()[..28]);
+        res
+    }
+
+    pub fn flush(&mut self) -> Vec<u8> {
+        self.finalize_reset().to_vec()
+    }
+}
+
+impl Default for Sha224<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Hasher<'_> {
     fn hash_block(&mut self, message: &[u8]) {
         assert_eq!(64, message.len());
@@ -154,12 +334,10 @@ impl Hasher<'_> {
         }
     }
 
-    fn reset(&mut self) -> Vec<u8> {
-        let mut res = Vec::with_capacity(32);
-        for (&ih, h) in self.tables.init_hash.iter().zip(&mut self.hash) {
-            res.write_u32::<BigEndian>(*h).unwrap();
-            *h = ih;
-        }
+    fn reset(&mut self) -> [u8; 32] {
+        let mut res = [0; 32];
+        BigEndian::write_u32_into(&self.hash, &mut res);
+        self.hash.copy_from_slice(&self.tables.init_hash);
         res
     }
 }
@@ -230,6 +408,17 @@ mod tests {
         assert_eq!(0x5be0cd19, TABLES.init_hash[7]);
     }
 
+    #[test]
+    fn test_sha224_init_hash() {
+        assert_eq!(
+            vec![
+                0xc1059ed8, 0x367cd507, 0x3070dd17, 0xf70e5939, //
+                0xffc00b31, 0x68581511, 0x64f98fa7, 0xbefa4fa4, //
+            ],
+            SHA224_TABLES.init_hash
+        );
+    }
+
     #[test]
     fn test_hash() {
         assert_eq!(
@@ -268,7 +457,177 @@ mod tests {
                 0xf1, 0x80, 0x9a, 0x48, 0xa4, 0x97, 0x20, 0x0e, //
                 0x04, 0x6d, 0x39, 0xcc, 0xc7, 0x11, 0x2c, 0xd0, //
             ],
-            Sha256::hash(iter::repeat(&v).take(1_000_000))
+            Sha256::hash(iter::repeat_n(&v, 1_000_000))
         );
     }
+
+    #[test]
+    fn test_flush_resets_length() {
+        let mut sha = Sha256::new();
+        sha.extend(&[0x78, 0x79, 0x7a]).flush();
+        assert_eq!(
+            Sha256::hash(&[0x61, 0x62, 0x63]),
+            sha.extend(&[0x61, 0x62, 0x63]).flush()
+        );
+    }
+
+    #[test]
+    fn test_clone() {
+        let mut sha = Sha256::new();
+        sha.extend(&[0x61, 0x62]);
+        let mut copy = sha.clone();
+        assert_eq!(
+            Sha256::hash(&[0x61, 0x62, 0x63]),
+            copy.extend(&[0x63]).flush()
+        );
+
+        copy.clone_from(&sha);
+        assert_eq!(
+            Sha256::hash(&[0x61, 0x62, 0x64]),
+            copy.extend(&[0x64]).flush()
+        );
+        assert_eq!(Sha256::hash(&[0x61, 0x62]), sha.flush());
+    }
+
+    #[test]
+    fn test_finalize_copy() {
+        let message: Vec<u8> = (0..=255).cycle().take(300).collect();
+        let mut sha = Sha256::new();
+        let mut sha224 = Sha224::new();
+        for i in 0..message.len() {
+            assert_eq!(Sha256::digest(&message[..i]), sha.finalize_copy());
+            assert_eq!(Sha224::digest(&message[..i]), sha224.finalize_copy());
+            sha.update(&message[i..=i]).unwrap();
+            sha224.update(&message[i..=i]).unwrap();
+        }
+        assert_eq!(Sha256::digest(&message), sha.finalize());
+        assert_eq!(Sha224::digest(&message), sha224.finalize());
+    }
+
+    fn near_limit() -> Sha256<'static> {
+        Sha256::from_state(&Sha256State {
+            hash: [0; 8],
+            buffer: vec![0; 60],
+            length: MAX_LENGTH - 3,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_length_limit() {
+        let mut sha = near_limit();
+        sha.update(&[0; 3]).unwrap();
+        assert_eq!(Err(LengthOverflow), sha.update(&[0]).map(|_| ()));
+        assert_eq!(MAX_LENGTH, sha.state().length);
+
+        let mut expected = near_limit();
+        expected.update(&[0; 3]).unwrap();
+        assert_eq!(expected.finalize(), sha.finalize());
+
+        let mut sha = near_limit();
+        assert!(sha.update(&[0; 4]).is_err());
+        assert_eq!(MAX_LENGTH - 3, sha.state().length);
+    }
+
+    #[test]
+    fn test_length_overflow() {
+        let mut sha = near_limit();
+        assert_eq!(Err(LengthOverflow), sha.try_extend(&[0; 4]).map(|_| ()));
+        assert_eq!(near_limit().state(), sha.state());
+    }
+
+    #[test]
+    fn test_sha224() {
+        assert_eq!(
+            vec![
+                0x23, 0x09, 0x7d, 0x22, 0x34, 0x05, 0xd8, 0x22, //
+                0x86, 0x42, 0xa4, 0x77, 0xbd, 0xa2, 0x55, 0xb3, //
+                0x2a, 0xad, 0xbc, 0xe4, 0xbd, 0xa0, 0xb3, 0xf7, //
+                0xe3, 0x6c, 0x9d, 0xa7, //
+            ],
+            Sha224::hash(&[0x61, 0x62, 0x63])
+        );
+        assert_eq!(
+            vec![
+                0x75, 0x38, 0x8b, 0x16, 0x51, 0x27, 0x76, 0xcc, //
+                0x5d, 0xba, 0x5d, 0xa1, 0xfd, 0x89, 0x01, 0x50, //
+                0xb0, 0xc6, 0x45, 0x5c, 0xb4, 0xf5, 0x8b, 0x19, //
+                0x52, 0x52, 0x25, 0x25, //
+            ],
+            Sha224::hash(&b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"[..])
+        );
+        let v = 0x61;
+        assert_eq!(
+            vec![
+                0x20, 0x79, 0x46, 0x55, 0x98, 0x0c, 0x91, 0xd8, //
+                0xbb, 0xb4, 0xc1, 0xea, 0x97, 0x61, 0x8a, 0x4b, //
+                0xf0, 0x3f, 0x42, 0x58, 0x19, 0x48, 0xb2, 0xee, //
+                0x4e, 0xe7, 0xad, 0x67, //
+            ],
+            Sha224::hash(iter::repeat_n(&v, 1_000_000))
+        );
+    }
+
+    #[test]
+    fn test_finalize() {
+        let expected = Sha256::hash(&[0x61, 0x62, 0x63]);
+        assert_eq!(expected, Sha256::digest(&[0x61, 0x62, 0x63]));
+
+        let mut sha = Sha256::new();
+        sha.extend(&[0x61, 0x62, 0x63]);
+        assert_eq!(expected, sha.clone().finalize());
+
+        let mut out = [0; 32];
+        sha.clone().finalize_into(&mut out);
+        assert_eq!(expected, out);
+
+        assert_eq!(expected, sha.finalize_reset());
+        assert_eq!(expected, sha.extend(&[0x61, 0x62, 0x63]).finalize_reset());
+    }
+
+    #[test]
+    fn test_sha224_finalize() {
+        let expected = Sha224::hash(&[0x61, 0x62, 0x63]);
+        assert_eq!(expected, Sha224::digest(&[0x61, 0x62, 0x63]));
+
+        let mut sha = Sha224::new();
+        sha.extend(&[0x61, 0x62, 0x63]);
+        let mut out = [0; 28];
+        sha.clone().finalize_into(&mut out);
+        assert_eq!(expected, out);
+        assert_eq!(expected, sha.finalize_reset());
+        sha.extend(&[0x61, 0x62, 0x63]);
+        assert_eq!(expected, sha.finalize());
+    }
+
+    #[test]
+    fn test_update() {
+        let message: Vec<u8> = (0..=255).cycle().take(1000).collect();
+        let expected = Sha256::digest(&message);
+        assert_eq!(
+            expected,
+            Sha256::new().update(&message).unwrap().finalize_reset()
+        );
+
+        for &split in &[0, 1, 63, 64, 65, 127, 128, 500, 1000] {
+            let mut sha = Sha256::new();
+            sha.update(&message[..split])
+                .unwrap()
+                .update(&message[split..])
+                .unwrap();
+            assert_eq!(expected, sha.finalize());
+
+            let mut sha = Sha256::new();
+            sha.extend(&message[..split])
+                .update(&message[split..])
+                .unwrap();
+            assert_eq!(expected, sha.finalize());
+        }
+
+        let mut sha = Sha256::new();
+        for chunk in message.chunks(7) {
+            sha.update(chunk).unwrap();
+        }
+        assert_eq!(expected, sha.finalize());
+    }
 }
@@ -0,0 +1,77 @@
+use super::Sha256;
+use ::digest::consts::{U32, U64};
+use ::digest::core_api::BlockSizeUser;
+use ::digest::{FixedOutput, FixedOutputReset, HashMarker, Output, OutputSizeUser, Reset, Update};
+
+impl HashMarker for Sha256<'_> {}
+
+impl BlockSizeUser for Sha256<'_> {
+    type BlockSize = U64;
+}
+
+impl OutputSizeUser for Sha256<'_> {
+    type OutputSize = U32;
+}
+
+impl Update for Sha256<'_> {
+    fn update(&mut self, data: &[u8]) {
+        Sha256::update(self, data).expect("message exceeds the SHA-256 length limit");
+    }
+}
+
+impl FixedOutput for Sha256<'_> {
+    fn finalize_into(mut self, out: &mut Output<Self>) {
+        out.copy_from_slice(&self.finalize_reset());
+    }
+}
+
+impl FixedOutputReset for Sha256<'_> {
+    fn finalize_into_reset(&mut self, out: &mut Output<Self>) {
+        out.copy_from_slice(&self.finalize_reset());
+    }
+}
+
+impl Reset for Sha256<'_> {
+    fn reset(&mut self) {
+        self.message_len = 0;
+        self.length = 0;
+        self.hash.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::digest::Digest;
+
+    fn generic_digest<D: Digest>(message: &[u8]) -> Vec<u8> {
+        let mut hasher = D::new();
+        hasher.update(&message[..10]);
+        hasher.update(&message[10..]);
+        hasher.finalize().to_vec()
+    }
+
+    #[test]
+    fn test_digest() {
+        let message: Vec<u8> = (0..=255).cycle().take(1000).collect();
+        assert_eq!(Sha256::hash(&message), generic_digest::<Sha256>(&message));
+        assert_eq!(
+            Sha256::hash(&message),
+            <Sha256 as Digest>::digest(&message).to_vec()
+        );
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut hasher = <Sha256 as Digest>::new();
+        Digest::update(&mut hasher, b"discarded");
+        Digest::reset(&mut hasher);
+        Digest::update(&mut hasher, b"abc");
+        assert_eq!(
+            Sha256::hash(b"abc"),
+            Digest::finalize_reset(&mut hasher).to_vec()
+        );
+        Digest::update(&mut hasher, b"abc");
+        assert_eq!(Sha256::hash(b"abc"), Digest::finalize(hasher).to_vec());
+    }
+}
@@ -0,0 +1,124 @@
+use super::{LengthOverflow, Sha256};
+use std::io;
+use std::io::{Read, Write};
+
+impl From<LengthOverflow> for io::Error {
+    fn from(e: LengthOverflow) -> Self {
+        io::Error::new(io::ErrorKind::InvalidInput, e)
+    }
+}
+
+impl Write for Sha256<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Sha256<'_> {
+    pub fn hash_reader<R: Read>(mut reader: R) -> io::Result<[u8; 32]> {
+        let mut sha = Sha256::new();
+        io::copy(&mut reader, &mut sha)?;
+        Ok(sha.finalize())
+    }
+}
+
+pub struct HashingReader<R> {
+    inner: R,
+    sha: Sha256<'static>,
+}
+
+impl<R: Read> HashingReader<R> {
+    pub fn new(inner: R) -> HashingReader<R> {
+        HashingReader {
+            inner,
+            sha: Sha256::new(),
+        }
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn finalize(self) -> [u8; 32] {
+        self.sha.finalize()
+    }
+
+    pub fn into_parts(self) -> (R, [u8; 32]) {
+        (self.inner, self.sha.finalize())
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.sha.update(&buf[..n])?;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sha::Sha256State;
+    use std::io::Cursor;
+
+    fn message() -> Vec<u8> {
+        (0..=255).cycle().take(100_000).collect()
+    }
+
+    #[test]
+    fn test_write() {
+        let message = message();
+        let mut sha = Sha256::new();
+        io::copy(&mut Cursor::new(&message), &mut sha).unwrap();
+        assert_eq!(Sha256::digest(&message), sha.finalize());
+    }
+
+    #[test]
+    fn test_write_length_overflow() {
+        let mut sha = Sha256::from_state(&Sha256State {
+            hash: [0; 8],
+            buffer: vec![0; 63],
+            length: u64::MAX / 8,
+        })
+        .unwrap();
+        let e = sha.write(&[0]).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidInput, e.kind());
+    }
+
+    #[test]
+    fn test_hash_reader() {
+        let message = message();
+        assert_eq!(
+            Sha256::digest(&message),
+            Sha256::hash_reader(Cursor::new(&message)).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_hashing_reader() {
+        let message = message();
+        let mut reader = HashingReader::new(Cursor::new(&message));
+        let mut copy = Vec::new();
+        reader.read_to_end(&mut copy).unwrap();
+        assert_eq!(message, copy);
+        assert_eq!(message.len() as u64, reader.get_ref().position());
+
+        let (_, digest) = reader.into_parts();
+        assert_eq!(Sha256::digest(&message), digest);
+    }
+
+    #[test]
+    fn test_hashing_reader_partial() {
+        let message = message();
+        let mut reader = HashingReader::new(Cursor::new(&message));
+        let mut head = [0; 1000];
+        reader.read_exact(&mut head).unwrap();
+        assert_eq!(Sha256::digest(&message[..1000]), reader.finalize());
+    }
+}
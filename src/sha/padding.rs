@@ -1,31 +1,43 @@
 use std::iter;
 use std::iter::Extend;
 
-pub fn pad_448(message: &mut Vec<u8>) {
+pub fn pad_448(blocks: &mut [u8; 128], l: usize) -> usize {
+    let padded = l + 1 + (119 - l % 64) % 64;
+    blocks[l] = 0x80;
+    blocks[l + 1..padded].iter_mut().for_each(|b| *b = 0);
+    padded
+}
+
+pub fn pad_896(message: &mut Vec<u8>) {
     let l = message.len();
     message.push(0x80);
-    message.extend(iter::repeat(0).take((63 + (-72 - l as i8) % 64) as usize));
+    message.extend(iter::repeat_n(0, (239 - l % 128) % 128));
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn padded_448(message: &[u8]) -> Vec<u8> {
+        let mut blocks = [0xff; 128];
+        blocks[..message.len()].copy_from_slice(message);
+        let l = pad_448(&mut blocks, message.len());
+        blocks[..l].to_vec()
+    }
+
     #[test]
     fn test_pad_448_with_single_byte() {
-        let mut message = vec![0xae];
-        pad_448(&mut message);
+        let message = padded_448(&[0xae]);
         assert_eq!(56, message.len());
 
         let mut expected = vec![0xae, 0x80];
-        expected.extend(iter::repeat(0).take(54));
+        expected.extend(iter::repeat_n(0, 54));
         assert_eq!(message, expected);
     }
 
     #[test]
     fn test_pad_448_just_fits_448() {
-        let mut message = vec![0xae; 55];
-        pad_448(&mut message);
+        let message = padded_448(&[0xae; 55]);
         assert_eq!(56, message.len());
 
         let mut expected = vec![0xae; 55];
@@ -35,13 +47,57 @@ mod tests {
 
     #[test]
     fn test_pad_448_just_over_448() {
-        let mut message = vec![0xae; 56];
-        pad_448(&mut message);
+        let message = padded_448(&[0xae; 56]);
         assert_eq!(120, message.len());
 
         let mut expected = vec![0xae; 56];
         expected.push(0x80);
-        expected.extend(iter::repeat(0).take(63));
+        expected.extend(iter::repeat_n(0, 63));
+        assert_eq!(message, expected);
+    }
+
+    #[test]
+    fn test_pad_448_full_block() {
+        let message = padded_448(&[0xae; 63]);
+        assert_eq!(120, message.len());
+
+        let mut expected = vec![0xae; 63];
+        expected.push(0x80);
+        expected.extend(iter::repeat_n(0, 56));
+        assert_eq!(message, expected);
+    }
+
+    #[test]
+    fn test_pad_896_with_single_byte() {
+        let mut message = vec![0xae];
+        pad_896(&mut message);
+        assert_eq!(112, message.len());
+
+        let mut expected = vec![0xae, 0x80];
+        expected.extend(iter::repeat_n(0, 110));
+        assert_eq!(message, expected);
+    }
+
+    #[test]
+    fn test_pad_896_just_fits_896() {
+        let mut message = vec![0xae; 111];
+        pad_896(&mut message);
+        assert_eq!(112, message.len());
+
+        let mut expected = vec![0xae; 111];
+        expected.push(0x80);
+        assert_eq!(message, expected);
+    }
+
+    #[test]
+    fn test_pad_896_just_over_896() {
+        let mut message = vec![0xae; 112];
+        pad_896(&mut message);
+        assert_eq!(240, message.len());
+
+        let mut expected = vec![0xae; 112];
+        expected.push(0x80);
+        expected.extend(iter::repeat_n(0, 127));
         assert_eq!(message, expected);
     }
 }
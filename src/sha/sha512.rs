@@ -0,0 +1,508 @@
+use super::padding;
+use crate::prime;
+use byteorder::{BigEndian, ByteOrder, WriteBytesExt};
+use lazy_static::lazy_static;
+use std::cmp::Ordering;
+
+fn ch(x: u64, y: u64, z: u64) -> u64 {
+    (x & y) ^ (!x & z)
+}
+
+fn maj(x: u64, y: u64, z: u64) -> u64 {
+    (x & y) ^ (x & z) ^ (y & z)
+}
+
+fn sigma0(x: u64) -> u64 {
+    x.rotate_right(28) ^ x.rotate_right(34) ^ x.rotate_right(39)
+}
+
+fn sigma1(x: u64) -> u64 {
+    x.rotate_right(14) ^ x.rotate_right(18) ^ x.rotate_right(41)
+}
+
+fn expansion_sigma0(x: u64) -> u64 {
+    x.rotate_right(1) ^ x.rotate_right(8) ^ x >> 7
+}
+
+fn expansion_sigma1(x: u64) -> u64 {
+    x.rotate_right(19) ^ x.rotate_right(61) ^ x >> 6
+}
+
+fn mul(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let mut res = vec![0; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0;
+        for (j, &y) in b.iter().enumerate() {
+            let t = u128::from(x) * u128::from(y) + u128::from(res[i + j]) + carry;
+            res[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        res[i + b.len()] = carry as u64;
+    }
+    res
+}
+
+fn cmp(a: &[u64], b: &[u64]) -> Ordering {
+    for i in (0..a.len().max(b.len())).rev() {
+        let x = a.get(i).unwrap_or(&0);
+        let y = b.get(i).unwrap_or(&0);
+        if x != y {
+            return x.cmp(y);
+        }
+    }
+    Ordering::Equal
+}
+
+fn root_fpart64(p: u32, n: usize) -> u64 {
+    let mut target = vec![0; n];
+    target.push(u64::from(p));
+
+    let mut x: u128 = 0;
+    for bit in (0..72).rev() {
+        let candidate = x | 1 << bit;
+        let limbs = [candidate as u64, (candidate >> 64) as u64];
+        let mut power = vec![1];
+        for _ in 0..n {
+            power = mul(&power, &limbs);
+        }
+        if cmp(&power, &target) != Ordering::Greater {
+            x = candidate;
+        }
+    }
+    x as u64
+}
+
+pub fn to_root_fpart64(n: usize) -> impl FnMut(u32) -> u64 {
+    move |p| root_fpart64(p, n)
+}
+
+struct Tables {
+    round_constants: Vec<u64>,
+    init_hash: Vec<u64>,
+}
+
+fn truncated_init_hash(name: &str) -> Vec<u64> {
+    let tables = Tables {
+        round_constants: TABLES.round_constants.clone(),
+        init_hash: TABLES
+            .init_hash
+            .iter()
+            .map(|h| h ^ 0xa5a5_a5a5_a5a5_a5a5)
+            .collect(),
+    };
+    let digest = Sha512::with_tables(&tables, 64)
+        .extend(name.as_bytes())
+        .flush();
+    let mut init_hash = vec![0; 8];
+    BigEndian::read_u64_into(&digest, &mut init_hash);
+    init_hash
+}
+
+lazy_static! {
+    static ref TABLES: Tables = {
+        let mut primes = prime::Primes::new();
+        let round_constants: Vec<u64> = primes
+            .into_iter()
+            .take(80)
+            .map(to_root_fpart64(3))
+            .collect();
+        let init_hash: Vec<u64> = primes.into_iter().take(8).map(to_root_fpart64(2)).collect();
+        Tables {
+            round_constants,
+            init_hash,
+        }
+    };
+    static ref SHA384_TABLES: Tables = {
+        let mut primes = prime::Primes::new();
+        Tables {
+            round_constants: TABLES.round_constants.clone(),
+            init_hash: primes
+                .into_iter()
+                .skip(8)
+                .take(8)
+                .map(to_root_fpart64(2))
+                .collect(),
+        }
+    };
+    static ref SHA512_224_TABLES: Tables = Tables {
+        round_constants: TABLES.round_constants.clone(),
+        init_hash: truncated_init_hash("SHA-512/224"),
+    };
+    static ref SHA512_256_TABLES: Tables = Tables {
+        round_constants: TABLES.round_constants.clone(),
+        init_hash: truncated_init_hash("SHA-512/256"),
+    };
+}
+
+#[derive(Clone)]
+pub struct Sha512<'a> {
+    message: Vec<u8>,
+    length: u128,
+    size: usize,
+    hash: Hasher<'a>,
+}
+
+#[derive(Clone)]
+pub struct Sha384<'a> {
+    sha: Sha512<'a>,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Clone)]
+pub struct Sha512_224<'a> {
+    sha: Sha512<'a>,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Clone)]
+pub struct Sha512_256<'a> {
+    sha: Sha512<'a>,
+}
+
+#[derive(Clone)]
+struct Hasher<'a> {
+    hash: [u64; 8],
+    tables: &'a Tables,
+    round_keys: [u64; 80],
+}
+
+impl Sha512<'_> {
+    pub fn new<'a>() -> Sha512<'a> {
+        Self::with_tables(&TABLES, 64)
+    }
+
+    fn with_tables(tables: &Tables, size: usize) -> Sha512<'_> {
+        let mut res = Sha512 {
+            message: Vec::with_capacity(256),
+            length: 0,
+            size,
+            hash: Hasher {
+                tables,
+                hash: [0; 8],
+                round_keys: [0; 80],
+            },
+        };
+        res.hash.reset();
+        res
+    }
+
+    pub fn hash<'a, T: IntoIterator<Item = &'a u8>>(message: T) -> Vec<u8> {
+        Self::new().extend(message).flush()
+    }
+
+    pub fn extend<'a, T: IntoIterator<Item = &'a u8>>(&mut self, message: T) -> &mut Self {
+        let mut iter = message.into_iter();
+        loop {
+            let l = self.message.len();
+            self.message.extend(iter.by_ref().take(128 - l));
+            self.length += (self.message.len() - l) as u128;
+            if self.message.len() < 128 {
+                return self;
+            }
+            self.hash.hash_block(&self.message);
+            self.message.clear();
+        }
+    }
+
+    fn hash_message_remainder(&mut self) {
+        padding::pad_896(&mut self.message);
+        self.message
+            .write_u128::<BigEndian>(self.length * 8)
+            .unwrap();
+        for block in self.message.chunks(128) {
+            self.hash.hash_block(block);
+        }
+    }
+
+    pub fn flush(&mut self) -> Vec<u8> {
+        self.hash_message_remainder();
+        self.message.clear();
+        self.length = 0;
+        let mut res = self.hash.reset();
+        res.truncate(self.size);
+        res
+    }
+}
+
+impl Default for Sha512<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sha384<'_> {
+    pub fn new<'a>() -> Sha384<'a> {
+        Sha384 {
+            sha: Sha512::with_tables(&SHA384_TABLES, 48),
+        }
+    }
+
+    pub fn hash<'a, T: IntoIterator<Item = &'a u8>>(message: T) -> Vec<u8> {
+        Self::new().extend(message).flush()
+    }
+
+    pub fn extend<'a, T: IntoIterator<Item = &'a u8>>(&mut self, message: T) -> &mut Self {
+        self.sha.extend(message);
+        self
+    }
+
+    pub fn flush(&mut self) -> Vec<u8> {
+        self.sha.flush()
+    }
+}
+
+impl Default for Sha384<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sha512_224<'_> {
+    pub fn new<'a>() -> Sha512_224<'a> {
+        Sha512_224 {
+            sha: Sha512::with_tables(&SHA512_224_TABLES, 28),
+        }
+    }
+
+    pub fn hash<'a, T: IntoIterator<Item = &'a u8>>(message: T) -> Vec<u8> {
+        Self::new().extend(message).flush()
+    }
+
+    pub fn extend<'a, T: IntoIterator<Item = &'a u8>>(&mut self, message: T) -> &mut Self {
+        self.sha.extend(message);
+        self
+    }
+
+    pub fn flush(&mut self) -> Vec<u8> {
+        self.sha.flush()
+    }
+}
+
+impl Default for Sha512_224<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sha512_256<'_> {
+    pub fn new<'a>() -> Sha512_256<'a> {
+        Sha512_256 {
+            sha: Sha512::with_tables(&SHA512_256_TABLES, 32),
+        }
+    }
+
+    pub fn hash<'a, T: IntoIterator<Item = &'a u8>>(message: T) -> Vec<u8> {
+        Self::new().extend(message).flush()
+    }
+
+    pub fn extend<'a, T: IntoIterator<Item = &'a u8>>(&mut self, message: T) -> &mut Self {
+        self.sha.extend(message);
+        self
+    }
+
+    pub fn flush(&mut self) -> Vec<u8> {
+        self.sha.flush()
+    }
+}
+
+impl Default for Sha512_256<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher<'_> {
+    fn expand_block(&mut self, block: &[u8]) {
+        let w = &mut self.round_keys;
+        BigEndian::read_u64_into(block, &mut w[0..16]);
+
+        for i in 16..80 {
+            w[i] = expansion_sigma1(w[i - 2])
+                .wrapping_add(w[i - 7])
+                .wrapping_add(expansion_sigma0(w[i - 15]))
+                .wrapping_add(w[i - 16]);
+        }
+    }
+
+    fn hash_block(&mut self, message: &[u8]) {
+        assert_eq!(128, message.len());
+        self.expand_block(message);
+        let mut r = self.hash;
+        for (&ki, &wi) in self
+            .tables
+            .round_constants
+            .iter()
+            .zip(self.round_keys[..].iter())
+        {
+            let t1 = r[7]
+                .wrapping_add(sigma1(r[4]))
+                .wrapping_add(ch(r[4], r[5], r[6]))
+                .wrapping_add(ki)
+                .wrapping_add(wi);
+            let t2 = sigma0(r[0]).wrapping_add(maj(r[0], r[1], r[2]));
+            r[7] = r[6];
+            r[6] = r[5];
+            r[5] = r[4];
+            r[4] = r[3].wrapping_add(t1);
+            r[3] = r[2];
+            r[2] = r[1];
+            r[1] = r[0];
+            r[0] = t1.wrapping_add(t2);
+        }
+        for (h, &r) in self.hash.iter_mut().zip(&r) {
+            *h = h.wrapping_add(r);
+        }
+    }
+
+    fn reset(&mut self) -> Vec<u8> {
+        let mut res = Vec::with_capacity(64);
+        for (&ih, h) in self.tables.init_hash.iter().zip(&mut self.hash) {
+            res.write_u64::<BigEndian>(*h).unwrap();
+            *h = ih;
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSAGE: &[u8] = b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmn\
+        hijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu";
+
+    #[test]
+    fn test_root_fpart64() {
+        assert_eq!(0x6a09e667f3bcc908, root_fpart64(2, 2));
+        assert_eq!(0x428a2f98d728ae22, root_fpart64(2, 3));
+        assert_eq!(0x6c44198c4a475817, root_fpart64(409, 3));
+    }
+
+    #[test]
+    fn test_round_constants() {
+        assert_eq!(80, TABLES.round_constants.len());
+        assert_eq!(0x428a2f98d728ae22, TABLES.round_constants[0]);
+        assert_eq!(0x7137449123ef65cd, TABLES.round_constants[1]);
+        assert_eq!(0xb5c0fbcfec4d3b2f, TABLES.round_constants[2]);
+        assert_eq!(0x6c44198c4a475817, TABLES.round_constants[79]);
+    }
+
+    #[test]
+    fn test_init_hash() {
+        assert_eq!(0x6a09e667f3bcc908, TABLES.init_hash[0]);
+        assert_eq!(0x5be0cd19137e2179, TABLES.init_hash[7]);
+        assert_eq!(0xcbbb9d5dc1059ed8, SHA384_TABLES.init_hash[0]);
+        assert_eq!(0x47b5481dbefa4fa4, SHA384_TABLES.init_hash[7]);
+        assert_eq!(0x8c3d37c819544da2, SHA512_224_TABLES.init_hash[0]);
+        assert_eq!(0x1112e6ad91d692a1, SHA512_224_TABLES.init_hash[7]);
+        assert_eq!(0x22312194fc2bf72c, SHA512_256_TABLES.init_hash[0]);
+        assert_eq!(0x0eb72ddc81c52ca2, SHA512_256_TABLES.init_hash[7]);
+    }
+
+    #[test]
+    fn test_sha512() {
+        assert_eq!(
+            vec![
+                0xdd, 0xaf, 0x35, 0xa1, 0x93, 0x61, 0x7a, 0xba, //
+                0xcc, 0x41, 0x73, 0x49, 0xae, 0x20, 0x41, 0x31, //
+                0x12, 0xe6, 0xfa, 0x4e, 0x89, 0xa9, 0x7e, 0xa2, //
+                0x0a, 0x9e, 0xee, 0xe6, 0x4b, 0x55, 0xd3, 0x9a, //
+                0x21, 0x92, 0x99, 0x2a, 0x27, 0x4f, 0xc1, 0xa8, //
+                0x36, 0xba, 0x3c, 0x23, 0xa3, 0xfe, 0xeb, 0xbd, //
+                0x45, 0x4d, 0x44, 0x23, 0x64, 0x3c, 0xe8, 0x0e, //
+                0x2a, 0x9a, 0xc9, 0x4f, 0xa5, 0x4c, 0xa4, 0x9f, //
+            ],
+            Sha512::hash(&[0x61, 0x62, 0x63])
+        );
+        assert_eq!(
+            vec![
+                0x8e, 0x95, 0x9b, 0x75, 0xda, 0xe3, 0x13, 0xda, //
+                0x8c, 0xf4, 0xf7, 0x28, 0x14, 0xfc, 0x14, 0x3f, //
+                0x8f, 0x77, 0x79, 0xc6, 0xeb, 0x9f, 0x7f, 0xa1, //
+                0x72, 0x99, 0xae, 0xad, 0xb6, 0x88, 0x90, 0x18, //
+                0x50, 0x1d, 0x28, 0x9e, 0x49, 0x00, 0xf7, 0xe4, //
+                0x33, 0x1b, 0x99, 0xde, 0xc4, 0xb5, 0x43, 0x3a, //
+                0xc7, 0xd3, 0x29, 0xee, 0xb6, 0xdd, 0x26, 0x54, //
+                0x5e, 0x96, 0xe5, 0x5b, 0x87, 0x4b, 0xe9, 0x09, //
+            ],
+            Sha512::hash(MESSAGE)
+        );
+    }
+
+    #[test]
+    fn test_sha384() {
+        assert_eq!(
+            vec![
+                0xcb, 0x00, 0x75, 0x3f, 0x45, 0xa3, 0x5e, 0x8b, //
+                0xb5, 0xa0, 0x3d, 0x69, 0x9a, 0xc6, 0x50, 0x07, //
+                0x27, 0x2c, 0x32, 0xab, 0x0e, 0xde, 0xd1, 0x63, //
+                0x1a, 0x8b, 0x60, 0x5a, 0x43, 0xff, 0x5b, 0xed, //
+                0x80, 0x86, 0x07, 0x2b, 0xa1, 0xe7, 0xcc, 0x23, //
+                0x58, 0xba, 0xec, 0xa1, 0x34, 0xc8, 0x25, 0xa7, //
+            ],
+            Sha384::hash(&[0x61, 0x62, 0x63])
+        );
+        assert_eq!(
+            vec![
+                0x09, 0x33, 0x0c, 0x33, 0xf7, 0x11, 0x47, 0xe8, //
+                0x3d, 0x19, 0x2f, 0xc7, 0x82, 0xcd, 0x1b, 0x47, //
+                0x53, 0x11, 0x1b, 0x17, 0x3b, 0x3b, 0x05, 0xd2, //
+                0x2f, 0xa0, 0x80, 0x86, 0xe3, 0xb0, 0xf7, 0x12, //
+                0xfc, 0xc7, 0xc7, 0x1a, 0x55, 0x7e, 0x2d, 0xb9, //
+                0x66, 0xc3, 0xe9, 0xfa, 0x91, 0x74, 0x60, 0x39, //
+            ],
+            Sha384::hash(MESSAGE)
+        );
+    }
+
+    #[test]
+    fn test_sha512_224() {
+        assert_eq!(
+            vec![
+                0x46, 0x34, 0x27, 0x0f, 0x70, 0x7b, 0x6a, 0x54, //
+                0xda, 0xae, 0x75, 0x30, 0x46, 0x08, 0x42, 0xe2, //
+                0x0e, 0x37, 0xed, 0x26, 0x5c, 0xee, 0xe9, 0xa4, //
+                0x3e, 0x89, 0x24, 0xaa, //
+            ],
+            Sha512_224::hash(&[0x61, 0x62, 0x63])
+        );
+        assert_eq!(
+            vec![
+                0x23, 0xfe, 0xc5, 0xbb, 0x94, 0xd6, 0x0b, 0x23, //
+                0x30, 0x81, 0x92, 0x64, 0x0b, 0x0c, 0x45, 0x33, //
+                0x35, 0xd6, 0x64, 0x73, 0x4f, 0xe4, 0x0e, 0x72, //
+                0x68, 0x67, 0x4a, 0xf9, //
+            ],
+            Sha512_224::hash(MESSAGE)
+        );
+    }
+
+    #[test]
+    fn test_sha512_256() {
+        assert_eq!(
+            vec![
+                0x53, 0x04, 0x8e, 0x26, 0x81, 0x94, 0x1e, 0xf9, //
+                0x9b, 0x2e, 0x29, 0xb7, 0x6b, 0x4c, 0x7d, 0xab, //
+                0xe4, 0xc2, 0xd0, 0xc6, 0x34, 0xfc, 0x6d, 0x46, //
+                0xe0, 0xe2, 0xf1, 0x31, 0x07, 0xe7, 0xaf, 0x23, //
+            ],
+            Sha512_256::hash(&[0x61, 0x62, 0x63])
+        );
+        assert_eq!(
+            vec![
+                0x39, 0x28, 0xe1, 0x84, 0xfb, 0x86, 0x90, 0xf8, //
+                0x40, 0xda, 0x39, 0x88, 0x12, 0x1d, 0x31, 0xbe, //
+                0x65, 0xcb, 0x9d, 0x3e, 0xf8, 0x3e, 0xe6, 0x14, //
+                0x6f, 0xea, 0xc8, 0x61, 0xe1, 0x9b, 0x56, 0x3a, //
+            ],
+            Sha512_256::hash(MESSAGE)
+        );
+    }
+
+    #[test]
+    fn test_flush_resets() {
+        let mut sha = Sha512::new();
+        sha.extend(&[0x78, 0x79, 0x7a]).flush();
+        assert_eq!(Sha512::hash(MESSAGE), sha.extend(MESSAGE).flush());
+    }
+}
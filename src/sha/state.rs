@@ -0,0 +1,145 @@
+use super::{Sha256, MAX_LENGTH};
+use byteorder::{BigEndian, ByteOrder};
+use std::error;
+use std::fmt;
+
+const HEADER_SIZE: usize = 40;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sha256State {
+    pub hash: [u32; 8],
+    pub buffer: Vec<u8>,
+    pub length: u64,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidState;
+
+impl fmt::Display for InvalidState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid SHA-256 state")
+    }
+}
+
+impl error::Error for InvalidState {}
+
+impl Sha256State {
+    fn validate(&self) -> Result<(), InvalidState> {
+        if self.buffer.len() < 64
+            && self.length <= MAX_LENGTH
+            && self.length % 64 == self.buffer.len() as u64
+        {
+            Ok(())
+        } else {
+            Err(InvalidState)
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut res = vec![0; HEADER_SIZE];
+        BigEndian::write_u32_into(&self.hash, &mut res[..32]);
+        BigEndian::write_u64(&mut res[32..], self.length);
+        res.extend(&self.buffer);
+        res
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Sha256State, InvalidState> {
+        if bytes.len() < HEADER_SIZE {
+            return Err(InvalidState);
+        }
+
+        let mut hash = [0; 8];
+        BigEndian::read_u32_into(&bytes[..32], &mut hash);
+        let state = Sha256State {
+            hash,
+            buffer: bytes[HEADER_SIZE..].to_vec(),
+            length: BigEndian::read_u64(&bytes[32..HEADER_SIZE]),
+        };
+        state.validate()?;
+        Ok(state)
+    }
+}
+
+impl Sha256<'_> {
+    pub fn state(&self) -> Sha256State {
+        Sha256State {
+            hash: self.hash.hash,
+            buffer: self.message[..self.message_len].to_vec(),
+            length: self.length,
+        }
+    }
+
+    pub fn from_state<'a>(state: &Sha256State) -> Result<Sha256<'a>, InvalidState> {
+        state.validate()?;
+
+        let mut res = Sha256::new();
+        res.hash.hash = state.hash;
+        res.message[..state.buffer.len()].copy_from_slice(&state.buffer);
+        res.message_len = state.buffer.len();
+        res.length = state.length;
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message() -> Vec<u8> {
+        (0..=255).cycle().take(1000).collect()
+    }
+
+    #[test]
+    fn test_resume() {
+        let message = message();
+        let mut sha = Sha256::new();
+        sha.update(&message[..300]).unwrap();
+
+        let state = sha.state();
+        assert_eq!(300, state.length);
+        assert_eq!(message[256..300], state.buffer[..]);
+
+        let mut resumed = Sha256::from_state(&state).unwrap();
+        resumed.update(&message[300..]).unwrap();
+        assert_eq!(Sha256::digest(&message), resumed.finalize());
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let message = message();
+        let mut sha = Sha256::new();
+        sha.update(&message[..100]).unwrap();
+
+        let bytes = sha.state().to_bytes();
+        assert_eq!(HEADER_SIZE + 36, bytes.len());
+
+        let state = Sha256State::from_bytes(&bytes).unwrap();
+        assert_eq!(sha.state(), state);
+
+        let mut resumed = Sha256::from_state(&state).unwrap();
+        resumed.update(&message[100..]).unwrap();
+        assert_eq!(Sha256::digest(&message), resumed.finalize());
+    }
+
+    #[test]
+    fn test_invalid_state() {
+        let mut sha = Sha256::new();
+        sha.update(&message()[..100]).unwrap();
+        let bytes = sha.state().to_bytes();
+
+        assert_eq!(Err(InvalidState), Sha256State::from_bytes(&bytes[..39]));
+        assert_eq!(Err(InvalidState), Sha256State::from_bytes(&bytes[..75]));
+
+        let mut state = sha.state();
+        state.buffer.push(0);
+        assert!(Sha256::from_state(&state).is_err());
+
+        state.buffer = vec![0; 64];
+        state.length = 128;
+        assert!(Sha256::from_state(&state).is_err());
+
+        state.buffer = vec![0; 63];
+        state.length = u64::MAX;
+        assert!(Sha256::from_state(&state).is_err());
+    }
+}